@@ -0,0 +1,108 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+use chrono::{Date, Duration, TimeZone, Utc};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use sixpence::{
+    services::{insert_transaction, ledger_header},
+    Entry, Ledger, Status, Transaction,
+};
+use ulid::Ulid;
+
+const LEDGER_SIZES: [usize; 3] = [1_000, 10_000, 50_000];
+
+fn transaction(date: Date<Utc>, description: &str) -> Transaction {
+    Transaction::new(
+        date,
+        Ulid::new(),
+        Status::Reconciled,
+        description.to_owned(),
+        vec![
+            Entry::new("Assets:Checking", -450),
+            Entry::new("Expenses:Food", 450),
+        ],
+    )
+}
+
+fn start_date() -> Date<Utc> {
+    Utc.ymd(2000, 1, 1)
+}
+
+fn write_ledger(dir: &Path, size: usize) -> PathBuf {
+    let path = dir.join(format!("ledger-{}.dat", size));
+    let contents = std::iter::once(ledger_header())
+        .chain((0..size).map(|n| {
+            transaction(start_date() + Duration::days(n as i64 / 10), "Coffee").to_string()
+        }))
+        .collect::<Vec<String>>()
+        .join("\n\n");
+    fs::write(path.as_path(), contents).unwrap();
+
+    path
+}
+
+fn add_transaction(c: &mut Criterion) {
+    let dir = env::temp_dir().join(format!("sixpence-bench-{}", process::id()));
+    fs::create_dir_all(dir.as_path()).unwrap();
+    let working_copy = dir.join("ledger.dat");
+
+    let mut group = c.benchmark_group("add_transaction");
+    group.sample_size(20);
+
+    for size in LEDGER_SIZES {
+        let original = write_ledger(dir.as_path(), size);
+        let latest = start_date() + Duration::days(size as i64);
+        let reset = || {
+            fs::copy(original.as_path(), working_copy.as_path()).unwrap();
+        };
+
+        group.bench_with_input(BenchmarkId::new("rewrite", size), &size, |b, _| {
+            b.iter_batched(
+                reset,
+                |_| {
+                    let mut ledger = Ledger::open(working_copy.as_path()).unwrap();
+                    ledger
+                        .add_transaction(transaction(latest, "Lunch"))
+                        .unwrap();
+                    ledger.save().unwrap();
+                },
+                BatchSize::PerIteration,
+            )
+        });
+
+        group.bench_with_input(BenchmarkId::new("append", size), &size, |b, _| {
+            b.iter_batched(
+                reset,
+                |_| {
+                    insert_transaction(working_copy.as_path(), &transaction(latest, "Lunch"), 0)
+                        .unwrap()
+                },
+                BatchSize::PerIteration,
+            )
+        });
+
+        group.bench_with_input(BenchmarkId::new("splice", size), &size, |b, _| {
+            b.iter_batched(
+                reset,
+                |_| {
+                    insert_transaction(
+                        working_copy.as_path(),
+                        &transaction(start_date(), "Lunch"),
+                        0,
+                    )
+                    .unwrap()
+                },
+                BatchSize::PerIteration,
+            )
+        });
+    }
+
+    group.finish();
+    fs::remove_dir_all(dir).unwrap();
+}
+
+criterion_group!(benches, add_transaction);
+criterion_main!(benches);
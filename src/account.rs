@@ -1,48 +1,93 @@
-use getset::{CopyGetters, Getters};
+use std::cmp::Ordering;
 
-use crate::{transaction::Entry, ACCOUNTING};
+use chrono::{Date, Utc};
+use getset::{CopyGetters, Getters, Setters};
 
-#[derive(CopyGetters, Debug, Default, Eq, Getters, PartialEq, Ord)]
+use crate::{
+    amount::{Amount, Amounts},
+    commodity::Commodities,
+};
+
+#[derive(Clone, CopyGetters, Debug, Default, Eq, Getters, PartialEq, Setters)]
 pub struct Account {
     #[getset(get = "pub")]
     name: String,
 
+    /// The account's balance in every commodity posted to it.
+    #[getset(get = "pub", set = "pub")]
+    balance: Amounts,
+
+    /// The number of entries posted to the account.
     #[getset(get_copy = "pub")]
-    value: isize,
+    postings: usize,
+
+    #[getset(get_copy = "pub")]
+    first_posting: Option<Date<Utc>>,
+
+    #[getset(get_copy = "pub")]
+    last_posting: Option<Date<Utc>>,
 }
 
 impl Account {
-    pub fn format_value_as_currency(&self) -> String {
-        let padded_value = format!("{:0>width$}", self.value, width = 3);
-        let whole_part = &padded_value[..padded_value.len() - 2];
-        let decimal_part = &padded_value[padded_value.len() - 2..];
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            ..Default::default()
+        }
+    }
 
-        let value = format!("{}.{}", whole_part, decimal_part)
-            .parse::<f64>()
-            .unwrap();
+    /// Adds an entry's amount, posted on `date`, to the account's balance.
+    pub fn post(&mut self, date: Date<Utc>, amount: &Amount) {
+        self.balance.add(amount);
+        self.postings += 1;
+        self.first_posting = Some(self.first_posting.map_or(date, |first| first.min(date)));
+        self.last_posting = Some(self.last_posting.map_or(date, |last| last.max(date)));
+    }
 
-        ACCOUNTING.format_money(value)
+    /// Adds another account's balance and postings to this one, as when
+    /// rolling a child account up into its parent.
+    pub fn merge(&mut self, other: &Account) {
+        self.balance.merge(&other.balance);
+        self.postings += other.postings;
+        self.first_posting = match (self.first_posting, other.first_posting) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.last_posting = match (self.last_posting, other.last_posting) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
     }
-}
 
-impl PartialOrd for Account {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.name().partial_cmp(other.name())
+    /// How deep the account is in the account hierarchy, where top-level
+    /// accounts like `Assets` have a depth of 1.
+    pub fn depth(&self) -> usize {
+        self.name.split(':').count()
     }
-}
 
-impl From<&[&Entry]> for Account {
-    fn from(entries: &[&Entry]) -> Self {
-        entries.iter().fold(Account::default(), |acc, cur| {
-            let name = if acc.name().is_empty() {
-                cur.account().to_owned()
-            } else {
-                acc.name().to_owned()
-            };
+    /// The last segment of the account's name, e.g. `Checking` for
+    /// `Assets:Checking`.
+    pub fn leaf_name(&self) -> &str {
+        self.name.rsplit(':').next().unwrap_or_default()
+    }
 
-            let value = acc.value() + cur.value();
+    /// Formats the account's balance, with every commodity in it separated
+    /// by commas.
+    pub fn format_balance(&self, commodities: &Commodities) -> String {
+        commodities.format_all(&self.balance).join(", ")
+    }
+}
 
-            Self { name, value }
-        })
+impl Ord for Account {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.name
+            .cmp(&other.name)
+            .then_with(|| self.balance.cmp(&other.balance))
+    }
+}
+
+impl PartialOrd for Account {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
@@ -0,0 +1,323 @@
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
+
+use chrono::{Date, Utc};
+use getset::{CopyGetters, Getters};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    directive::Directive,
+    error::Error,
+    transaction::{
+        parse_date, parse_description, quote_description, split_with_columns, Transaction,
+    },
+};
+
+/// What kind of thing an account keeps track of.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum AccountType {
+    Asset,
+    Liability,
+    Equity,
+    Income,
+    Expense,
+}
+
+impl FromStr for AccountType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "asset" => Ok(Self::Asset),
+            "liability" => Ok(Self::Liability),
+            "equity" => Ok(Self::Equity),
+            "income" => Ok(Self::Income),
+            "expense" => Ok(Self::Expense),
+            _ => Err(Error::invalid_account_type(s)),
+        }
+    }
+}
+
+impl Display for AccountType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Asset => write!(f, "asset"),
+            Self::Liability => write!(f, "liability"),
+            Self::Equity => write!(f, "equity"),
+            Self::Income => write!(f, "income"),
+            Self::Expense => write!(f, "expense"),
+        }
+    }
+}
+
+/// The opening of an account, written to the ledger as
+/// `YYYY-MM-DD open <account> [type=<type>] ["<description>"]`, e.g.
+/// `2026-01-01 open Assets:Checking type=asset "Joint checking"`.
+#[derive(Clone, CopyGetters, Debug, Deserialize, Eq, Getters, PartialEq, Serialize)]
+pub struct AccountOpening {
+    #[getset(get_copy = "pub")]
+    #[serde(with = "crate::serde_date")]
+    date: Date<Utc>,
+
+    #[getset(get = "pub")]
+    account: String,
+
+    #[getset(get_copy = "pub")]
+    account_type: Option<AccountType>,
+
+    #[getset(get = "pub")]
+    description: String,
+}
+
+impl AccountOpening {
+    pub const KEYWORD: &'static str = "open";
+
+    pub fn new(
+        date: Date<Utc>,
+        account: &str,
+        account_type: Option<AccountType>,
+        description: &str,
+    ) -> Result<Self, Error> {
+        if account.trim().is_empty() {
+            return Err(Error::empty_account_name());
+        }
+
+        Ok(Self {
+            date,
+            account: account.trim().to_owned(),
+            account_type,
+            description: description.to_owned(),
+        })
+    }
+}
+
+impl TryFrom<&str> for AccountOpening {
+    type Error = Error;
+
+    fn try_from(line: &str) -> Result<Self, Self::Error> {
+        let (date, keyword_end) = parse_date_and_keyword(line)?;
+
+        let mut rest = &line[keyword_end..];
+        let mut description = String::new();
+        if let Some(quote) = rest.find('"') {
+            let offset = keyword_end + quote;
+            description =
+                parse_description(&rest[quote..]).map_err(|err| err.offset_columns(offset))?;
+            rest = &rest[..quote];
+        }
+
+        let mut account_type = None;
+        let parts = split_with_columns(rest);
+        if let Some((columns, part)) = parts.last() {
+            if let Some(name) = part.strip_prefix("type=") {
+                let columns = keyword_end + columns.start..keyword_end + columns.end;
+                account_type =
+                    Some(AccountType::from_str(name).map_err(|err| err.at_columns(columns))?);
+                rest = &rest[..parts[parts.len() - 1].0.start];
+            }
+        }
+
+        let end = line.trim_end().len();
+        Self::new(date, rest, account_type, description.as_str())
+            .map_err(|err| err.at_columns(end..end + 1))
+    }
+}
+
+impl Display for AccountOpening {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.date.format("%Y-%m-%d"),
+            Self::KEYWORD,
+            self.account
+        )?;
+        if let Some(account_type) = self.account_type {
+            write!(f, " type={}", account_type)?;
+        }
+        if !self.description.is_empty() {
+            write!(f, " {}", quote_description(self.description.as_str()))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The closing of an account, written to the ledger as
+/// `YYYY-MM-DD close <account>`. Nothing can be posted to the account after
+/// that day.
+#[derive(Clone, CopyGetters, Debug, Deserialize, Eq, Getters, PartialEq, Serialize)]
+pub struct AccountClosing {
+    #[getset(get_copy = "pub")]
+    #[serde(with = "crate::serde_date")]
+    date: Date<Utc>,
+
+    #[getset(get = "pub")]
+    account: String,
+}
+
+impl AccountClosing {
+    pub const KEYWORD: &'static str = "close";
+
+    pub fn new(date: Date<Utc>, account: &str) -> Result<Self, Error> {
+        if account.trim().is_empty() {
+            return Err(Error::empty_account_name());
+        }
+
+        Ok(Self {
+            date,
+            account: account.trim().to_owned(),
+        })
+    }
+}
+
+impl TryFrom<&str> for AccountClosing {
+    type Error = Error;
+
+    fn try_from(line: &str) -> Result<Self, Self::Error> {
+        let (date, keyword_end) = parse_date_and_keyword(line)?;
+
+        let end = line.trim_end().len();
+        Self::new(date, &line[keyword_end..]).map_err(|err| err.at_columns(end..end + 1))
+    }
+}
+
+impl Display for AccountClosing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.date.format("%Y-%m-%d"),
+            Self::KEYWORD,
+            self.account
+        )
+    }
+}
+
+/// Parses the date at the start of an account directive, returning it along
+/// with where its keyword ends.
+fn parse_date_and_keyword(line: &str) -> Result<(Date<Utc>, usize), Error> {
+    let parts = split_with_columns(line);
+    let end = line.trim_end().len();
+    let (date, keyword) = match parts.as_slice() {
+        [date, keyword, ..] => (date, keyword),
+        _ => {
+            return Err(
+                Error::invalid_directive("expected a date and a keyword").at_columns(end..end + 1)
+            )
+        }
+    };
+
+    let (date_columns, date) = date;
+    let date = parse_date(date, date_columns.clone())?;
+
+    Ok((date, keyword.0.end))
+}
+
+/// An account that's been declared in the ledger, with the days it's open.
+#[derive(Clone, CopyGetters, Debug, Eq, Getters, PartialEq)]
+pub struct AccountDeclaration {
+    #[getset(get = "pub")]
+    name: String,
+
+    #[getset(get_copy = "pub")]
+    opened: Date<Utc>,
+
+    /// The last day anything can be posted to the account, if it's been
+    /// closed.
+    #[getset(get_copy = "pub")]
+    closed: Option<Date<Utc>>,
+
+    #[getset(get_copy = "pub")]
+    account_type: Option<AccountType>,
+
+    #[getset(get = "pub")]
+    description: String,
+}
+
+impl AccountDeclaration {
+    /// Whether entries dated `date` can be posted to the account.
+    pub fn is_open_on(&self, date: Date<Utc>) -> bool {
+        self.opened <= date && self.closed.is_none_or(|closed| date <= closed)
+    }
+}
+
+/// Every account declared in a ledger, by name.
+#[derive(Clone, Debug, Default)]
+pub struct AccountDeclarations {
+    declarations: BTreeMap<String, AccountDeclaration>,
+}
+
+impl AccountDeclarations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records an account being opened. Opening an account that's already
+    /// declared replaces its declaration.
+    pub fn open(&mut self, opening: &AccountOpening) {
+        self.declarations.insert(
+            opening.account().to_owned(),
+            AccountDeclaration {
+                name: opening.account().to_owned(),
+                opened: opening.date(),
+                closed: None,
+                account_type: opening.account_type(),
+                description: opening.description().to_owned(),
+            },
+        );
+    }
+
+    /// Records an account being closed, if it's been declared.
+    pub fn close(&mut self, closing: &AccountClosing) {
+        if let Some(declaration) = self.declarations.get_mut(closing.account()) {
+            declaration.closed = Some(closing.date());
+        }
+    }
+
+    pub fn get(&self, account: &str) -> Option<&AccountDeclaration> {
+        self.declarations.get(account)
+    }
+
+    /// Every declared account, ordered by name.
+    pub fn iter(&self) -> impl Iterator<Item = &AccountDeclaration> {
+        self.declarations.values()
+    }
+
+    /// Checks that an entry dated `date` can be posted to `account`: that
+    /// it's been declared, and is open that day.
+    pub fn check_posting(&self, account: &str, date: Date<Utc>) -> Result<(), Error> {
+        match self.declarations.get(account) {
+            None => Err(Error::undeclared_account(account)),
+            Some(declaration) if !declaration.is_open_on(date) => {
+                Err(Error::account_not_open(account, date))
+            }
+            Some(_) => Ok(()),
+        }
+    }
+
+    /// Checks every entry of a transaction as [`check_posting`] does.
+    ///
+    /// [`check_posting`]: AccountDeclarations::check_posting
+    pub fn check_transaction(&self, transaction: &Transaction) -> Result<(), Error> {
+        transaction
+            .entries()
+            .iter()
+            .try_for_each(|entry| self.check_posting(entry.account(), transaction.date()))
+    }
+}
+
+impl<'a> FromIterator<&'a Directive> for AccountDeclarations {
+    /// Replays account directives, which have to come in date order.
+    fn from_iter<I: IntoIterator<Item = &'a Directive>>(directives: I) -> Self {
+        let mut declarations = Self::new();
+        for directive in directives {
+            match directive {
+                Directive::AccountOpening(opening) => declarations.open(opening),
+                Directive::AccountClosing(closing) => declarations.close(closing),
+                _ => {}
+            }
+        }
+
+        declarations
+    }
+}
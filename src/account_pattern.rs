@@ -0,0 +1,157 @@
+use std::str::FromStr;
+
+use regex::Regex;
+
+use crate::error::Error;
+
+/// A pattern that selects accounts by name.
+///
+/// Patterns are read according to the characters they contain:
+///
+/// * Anything with a regex metacharacter (`^ $ . + ( ) | { } \`) is a regular
+///   expression, matched anywhere in the account name.
+/// * Anything with a `*`, `?` or `[` is a glob, matched against the whole
+///   account name. `*` and `?` stay within a single level of the hierarchy,
+///   while `**` crosses levels.
+/// * Anything else matches accounts containing those whole levels, starting
+///   at any level, so `Expenses:Food` matches `Expenses:Food:Groceries` but
+///   not `Expenses:FoodTruck`, and `Retirement` matches
+///   `Assets:Retirement:IRA`.
+#[derive(Clone, Debug)]
+pub enum AccountPattern {
+    Levels(String),
+    Glob(Regex),
+    Regex(Regex),
+}
+
+impl AccountPattern {
+    pub fn matches(&self, account: &str) -> bool {
+        match self {
+            Self::Levels(levels) => {
+                format!(":{}:", account).contains(format!(":{}:", levels).as_str())
+            }
+            Self::Glob(regex) | Self::Regex(regex) => regex.is_match(account),
+        }
+    }
+}
+
+impl FromStr for AccountPattern {
+    type Err = Error;
+
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        if pattern.contains(['^', '$', '.', '+', '(', ')', '|', '{', '}', '\\']) {
+            Regex::new(pattern)
+                .map(Self::Regex)
+                .map_err(|err| Error::invalid_account_pattern(pattern, err.to_string().as_str()))
+        } else if pattern.contains(['*', '?', '[']) {
+            Regex::new(glob_to_regex(pattern).as_str())
+                .map(Self::Glob)
+                .map_err(|_| Error::invalid_account_pattern(pattern, "malformed glob"))
+        } else {
+            Ok(Self::Levels(pattern.trim_matches(':').to_owned()))
+        }
+    }
+}
+
+/// A set of patterns to include and exclude accounts by.
+#[derive(Clone, Debug, Default)]
+pub struct AccountFilter {
+    include: Vec<AccountPattern>,
+    exclude: Vec<AccountPattern>,
+}
+
+impl AccountFilter {
+    pub fn new(include: Vec<AccountPattern>, exclude: Vec<AccountPattern>) -> Self {
+        Self { include, exclude }
+    }
+
+    /// Whether an account matches any of the included patterns (or there are
+    /// none), and none of the excluded ones.
+    pub fn matches(&self, account: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|pattern| pattern.matches(account)))
+            && !self.exclude.iter().any(|pattern| pattern.matches(account))
+    }
+}
+
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                regex.push_str(".*");
+            }
+            '*' => regex.push_str("[^:]*"),
+            '?' => regex.push_str("[^:]"),
+            '[' => {
+                regex.push('[');
+                if chars.peek() == Some(&'!') {
+                    chars.next();
+                    regex.push('^');
+                }
+
+                for c in chars.by_ref() {
+                    if c == '\\' {
+                        regex.push('\\');
+                    }
+
+                    regex.push(c);
+                    if c == ']' {
+                        break;
+                    }
+                }
+            }
+            c => regex.push_str(regex::escape(c.to_string().as_str()).as_str()),
+        }
+    }
+
+    regex.push('$');
+    regex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, account: &str) -> bool {
+        pattern.parse::<AccountPattern>().unwrap().matches(account)
+    }
+
+    #[test]
+    fn matches_plain_patterns_as_whole_levels() {
+        assert!(matches("Assets", "Assets:Bank"));
+        assert!(matches("Food", "Expenses:Food:Groceries"));
+        assert!(matches("Expenses:Food", "Expenses:Food"));
+        assert!(matches("Retirement", "Assets:Retirement:IRA"));
+        assert!(!matches("Expenses:Food", "Expenses:FoodTruck"));
+        assert!(!matches("Exp", "Expenses:Food"));
+        assert!(!matches("Ban", "Assets:Bank"));
+    }
+
+    #[test]
+    fn excludes_accounts_with_matching_levels() {
+        let filter = AccountFilter::new(
+            vec!["^Assets".parse().unwrap()],
+            vec!["Retirement".parse().unwrap()],
+        );
+        assert!(filter.matches("Assets:Bank"));
+        assert!(!filter.matches("Assets:Retirement:IRA"));
+        assert!(!filter.matches("Expenses:Food"));
+    }
+
+    #[test]
+    fn matches_globs_against_the_whole_name() {
+        assert!(matches("Exp*", "Expenses"));
+        assert!(!matches("Exp*", "Expenses:Food"));
+        assert!(matches("Exp**", "Expenses:Food"));
+        assert!(matches("Assets:*:Checking", "Assets:Joint:Checking"));
+    }
+
+    #[test]
+    fn matches_regexes_anywhere_in_the_name() {
+        assert!(matches("^Assets", "Assets:Bank"));
+        assert!(matches("Food$", "Expenses:Food"));
+        assert!(!matches("^Food", "Expenses:Food"));
+    }
+}
@@ -0,0 +1,566 @@
+use std::{collections::BTreeMap, fmt::Display, ops::Neg};
+
+use getset::{CopyGetters, Getters};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    commodity::{Commodities, DEFAULT_COMMODITY},
+    currency::{CurrencyFormat, Grouping},
+    error::Error,
+};
+
+/// A quantity of a single commodity, counted in the commodity's smallest
+/// unit, e.g. cents for most currencies.
+#[derive(
+    Clone,
+    CopyGetters,
+    Debug,
+    Default,
+    Deserialize,
+    Eq,
+    Getters,
+    Hash,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    Serialize,
+)]
+pub struct Amount {
+    #[getset(get_copy = "pub")]
+    value: isize,
+
+    /// The commodity's symbol, or [`DEFAULT_COMMODITY`] for the ledger's
+    /// home currency.
+    #[getset(get = "pub")]
+    commodity: String,
+}
+
+impl Amount {
+    pub fn new(value: isize, commodity: &str) -> Self {
+        Self {
+            value,
+            commodity: commodity.to_owned(),
+        }
+    }
+
+    /// An amount of the ledger's home currency.
+    pub fn of_default(value: isize) -> Self {
+        Self::new(value, DEFAULT_COMMODITY)
+    }
+}
+
+impl Neg for Amount {
+    type Output = Amount;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            value: -self.value,
+            commodity: self.commodity,
+        }
+    }
+}
+
+impl Display for Amount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.commodity.as_str() {
+            DEFAULT_COMMODITY => write!(f, "{}", self.value),
+            commodity => write!(f, "{} {}", self.value, commodity),
+        }
+    }
+}
+
+/// A sum of amounts in any number of commodities, kept apart per commodity.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Amounts {
+    values: BTreeMap<String, isize>,
+}
+
+impl Amounts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, amount: &Amount) {
+        *self
+            .values
+            .entry(amount.commodity().to_owned())
+            .or_default() += amount.value();
+    }
+
+    pub fn merge(&mut self, other: &Amounts) {
+        for (commodity, value) in other.values.iter() {
+            *self.values.entry(commodity.to_owned()).or_default() += value;
+        }
+    }
+
+    /// The total of a single commodity.
+    pub fn get(&self, commodity: &str) -> isize {
+        self.values.get(commodity).copied().unwrap_or_default()
+    }
+
+    /// Every commodity with a non-zero total, sorted by symbol with the home
+    /// currency first.
+    pub fn iter(&self) -> impl Iterator<Item = Amount> + '_ {
+        self.values
+            .iter()
+            .filter(|(_, value)| **value != 0)
+            .map(|(commodity, value)| Amount::new(*value, commodity))
+    }
+
+    /// Whether every commodity adds up to zero.
+    pub fn is_zero(&self) -> bool {
+        self.values.values().all(|value| *value == 0)
+    }
+}
+
+impl<'a> FromIterator<&'a Amount> for Amounts {
+    fn from_iter<I: IntoIterator<Item = &'a Amount>>(amounts: I) -> Self {
+        let mut sum = Self::new();
+        for amount in amounts {
+            sum.add(amount);
+        }
+
+        sum
+    }
+}
+
+impl FromIterator<Amount> for Amounts {
+    fn from_iter<I: IntoIterator<Item = Amount>>(amounts: I) -> Self {
+        let mut sum = Self::new();
+        for amount in amounts {
+            sum.add(&amount);
+        }
+
+        sum
+    }
+}
+
+/// Whether `symbol` can name a commodity: it has to be a single word that
+/// can't be mistaken for a number.
+pub fn is_commodity_symbol(symbol: &str) -> bool {
+    match symbol.chars().next() {
+        Some(first) => {
+            !(first.is_ascii_digit()
+                || "+-.,@({".contains(first)
+                || symbol.chars().any(char::is_whitespace))
+        }
+        None => false,
+    }
+}
+
+/// The symbols that mark a value as being in the home currency: its own, or
+/// when it has none, any of the common ones.
+fn currency_symbols(commodities: &Commodities) -> Vec<&str> {
+    match commodities.currency_format().symbol().as_str() {
+        "" => vec!["$", "€", "£", "¥", "₹"],
+        symbol => vec![symbol],
+    }
+}
+
+/// How a value written without a decimal mark, like `1250`, is read.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum WholeNumbers {
+    /// As a count of the commodity's smallest unit, the way the ledger
+    /// stores values.
+    SmallestUnits,
+
+    /// As whole units of the commodity, the way values are typed.
+    WholeUnits,
+}
+
+/// Parses an amount written as a value optionally followed by a commodity,
+/// e.g. `1250`, `-$12.50`, `(1.234,50 €)` or `10.5 AAPL`. See
+/// [`parse_value`] for how values are read.
+pub fn parse_amount(text: &str, commodities: &Commodities) -> Result<Amount, Error> {
+    read_amount(text, commodities, WholeNumbers::SmallestUnits)
+}
+
+/// Parses an amount typed on the command line, like [`parse_amount`] except
+/// that a value without a decimal mark is a number of whole units, so `12`
+/// is 12.00 in the home currency and `10 AAPL` is ten shares.
+pub fn parse_typed_amount(text: &str, commodities: &Commodities) -> Result<Amount, Error> {
+    read_amount(text, commodities, WholeNumbers::WholeUnits)
+}
+
+fn read_amount(
+    text: &str,
+    commodities: &Commodities,
+    whole_numbers: WholeNumbers,
+) -> Result<Amount, Error> {
+    let text = text.trim();
+    if let Some((value, commodity)) = text.rsplit_once(char::is_whitespace) {
+        let (commodity, closing) = match commodity.strip_suffix(')') {
+            Some(commodity) => (commodity, ")"),
+            None => (commodity, ""),
+        };
+
+        if is_commodity_symbol(commodity) && !currency_symbols(commodities).contains(&commodity) {
+            let value = format!("{}{}", value.trim_end(), closing);
+            return read_value(value.as_str(), commodity, commodities, whole_numbers)
+                .map(|value| Amount::new(value, commodity));
+        }
+    }
+
+    read_value(text, DEFAULT_COMMODITY, commodities, whole_numbers).map(Amount::of_default)
+}
+
+/// Parses a written value of `commodity` into a count of its smallest unit.
+///
+/// A bare integer like `1250` or `-3` is already such a count, which is how
+/// ledgers have always stored values. Anything else is read as money in the
+/// home currency's format: an optional sign or parentheses, the currency
+/// symbol before or after, thousands separators, and a decimal mark followed
+/// by up to as many digits as the commodity's precision, padded with zeros,
+/// e.g. `-$1,234.5` or `(1.234,50 €)`. Since a bare integer counts the
+/// smallest unit, notation like that needs its decimal mark to say whether it
+/// means whole units.
+pub fn parse_value(text: &str, commodity: &str, commodities: &Commodities) -> Result<isize, Error> {
+    read_value(text, commodity, commodities, WholeNumbers::SmallestUnits)
+}
+
+fn read_value(
+    text: &str,
+    commodity: &str,
+    commodities: &Commodities,
+    whole_numbers: WholeNumbers,
+) -> Result<isize, Error> {
+    let original = text.trim();
+    let invalid = |reason: &str| Error::invalid_amount(original, reason);
+
+    let digits = original.trim_start_matches(['-', '+']);
+    if whole_numbers == WholeNumbers::SmallestUnits
+        && !digits.is_empty()
+        && original.len() - digits.len() <= 1
+        && digits.chars().all(|c| c.is_ascii_digit())
+    {
+        return original
+            .parse::<isize>()
+            .map_err(|_| invalid("it's too large"));
+    }
+
+    let format = commodities.currency_format();
+    let precision = commodities.precision(commodity);
+    let symbols = match commodity {
+        DEFAULT_COMMODITY => currency_symbols(commodities),
+        _ => vec![],
+    };
+
+    let mut text = original;
+    let mut negative = false;
+    if let Some(inner) = text.strip_prefix('(') {
+        text = inner
+            .strip_suffix(')')
+            .ok_or_else(|| invalid("missing the closing parenthesis"))?
+            .trim();
+        negative = true;
+    }
+
+    let mut has_sign = false;
+    let mut has_symbol = false;
+    loop {
+        if let Some(rest) = text.strip_prefix(['-', '+']) {
+            if has_sign || negative {
+                return Err(invalid("it has more than one sign"));
+            }
+            has_sign = true;
+            negative = text.starts_with('-');
+            text = rest.trim_start();
+        } else if let Some(symbol) = symbols.iter().find(|symbol| text.starts_with(**symbol)) {
+            if has_symbol {
+                return Err(invalid("it has more than one currency symbol"));
+            }
+            has_symbol = true;
+            text = text[symbol.len()..].trim_start();
+        } else {
+            break;
+        }
+    }
+    if let Some(symbol) = symbols.iter().find(|symbol| text.ends_with(**symbol)) {
+        if has_symbol {
+            return Err(invalid("it has more than one currency symbol"));
+        }
+        text = text[..text.len() - symbol.len()].trim_end();
+    }
+
+    if text.is_empty() {
+        return Err(invalid("expected a number"));
+    }
+    if let Some(c) = text.chars().find(|c| {
+        !(c.is_ascii_digit()
+            || format.thousands_separator().contains(*c)
+            || format.decimal_separator().contains(*c))
+    }) {
+        return Err(match c {
+            '$' | '€' | '£' | '¥' | '₹' if symbols.is_empty() => invalid(
+                format!(
+                    "a currency symbol can't go with the commodity {}",
+                    commodity
+                )
+                .as_str(),
+            ),
+            '$' | '€' | '£' | '¥' | '₹' => {
+                invalid(format!("the home currency's symbol is {}", symbols.join(" or ")).as_str())
+            }
+            c => invalid(format!("unexpected '{}'", c).as_str()),
+        });
+    }
+
+    let decimal_separator = format.decimal_separator().as_str();
+    let (whole, fraction) = match text.split_once(decimal_separator) {
+        Some((_, fraction)) if fraction.contains(decimal_separator) => {
+            return Err(invalid("it has more than one decimal mark"))
+        }
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (text, None),
+    };
+
+    let whole = ungroup(whole, format).map_err(|_| {
+        match (
+            fraction,
+            text.rsplit_once(format.thousands_separator().as_str()),
+        ) {
+            (None, Some((before, after))) if !after.is_empty() && after.len() != 3 => invalid(
+                format!(
+                    "'{}' separates thousands here, not decimals; write {}{}{} instead",
+                    format.thousands_separator(),
+                    before,
+                    format.decimal_separator(),
+                    after
+                )
+                .as_str(),
+            ),
+            _ => invalid("its thousands separators are misplaced"),
+        }
+    })?;
+
+    let fraction = match fraction {
+        Some(fraction) if !fraction.chars().all(|c| c.is_ascii_digit()) => {
+            return Err(invalid(
+                "thousands separators can't follow the decimal mark",
+            ))
+        }
+        Some(fraction) if fraction.trim_end_matches('0').len() > precision as usize => {
+            return Err(invalid(
+                match precision {
+                    0 => format!("{} has no decimal places", commodity_name(commodity)),
+                    1 => format!("{} has only 1 decimal place", commodity_name(commodity)),
+                    _ => format!(
+                        "{} has only {} decimal places",
+                        commodity_name(commodity),
+                        precision
+                    ),
+                }
+                .as_str(),
+            ))
+        }
+        Some(fraction) => format!("{:0<width$}", fraction, width = precision as usize)
+            [..precision as usize]
+            .to_owned(),
+        None if whole_numbers == WholeNumbers::WholeUnits => "0".repeat(precision as usize),
+        None if precision > 0 => {
+            return Err(invalid(
+                format!(
+                    "whole numbers count the smallest unit, so add a decimal mark for whole \
+                     units, like {}{}{}",
+                    text,
+                    format.decimal_separator(),
+                    "0".repeat(precision as usize)
+                )
+                .as_str(),
+            ))
+        }
+        None => String::new(),
+    };
+
+    let value = format!("{}{}", whole, fraction)
+        .trim_start_matches('0')
+        .parse::<isize>()
+        .or_else(|err| match err.kind() {
+            std::num::IntErrorKind::Empty => Ok(0),
+            _ => Err(invalid("it's too large")),
+        })?;
+
+    Ok(if negative { -value } else { value })
+}
+
+fn commodity_name(commodity: &str) -> &str {
+    match commodity {
+        DEFAULT_COMMODITY => "the home currency",
+        commodity => commodity,
+    }
+}
+
+/// Removes the thousands separators from the whole part of a value, checking
+/// that they're where the format's grouping puts them.
+fn ungroup(whole: &str, format: &CurrencyFormat) -> Result<String, ()> {
+    let separator = format.thousands_separator().as_str();
+    if separator.is_empty() || !whole.contains(separator) {
+        return match whole.chars().all(|c| c.is_ascii_digit()) {
+            true => Ok(whole.to_owned()),
+            false => Err(()),
+        };
+    }
+
+    let groups = whole.split(separator).collect::<Vec<&str>>();
+    let size = match format.grouping() {
+        Grouping::Thousands => 3,
+        Grouping::Indian => 2,
+    };
+    let last = groups.len() - 1;
+    let valid = groups.iter().enumerate().all(|(idx, group)| {
+        group.chars().all(|c| c.is_ascii_digit())
+            && match idx {
+                _ if idx == last => group.len() == 3,
+                0 => (1..=size).contains(&group.len()),
+                _ => group.len() == size,
+            }
+    });
+
+    match valid {
+        true => Ok(groups.concat()),
+        false => Err(()),
+    }
+}
+
+/// Splits a line of space-separated parts ending in an amount, returning the
+/// byte offset at which the amount starts, and the amount. The amount is
+/// either a value of the home currency, or a value followed by a commodity
+/// symbol, read as [`parse_amount`] does.
+pub(crate) fn split_trailing_amount(
+    parts: &[(std::ops::Range<usize>, &str)],
+    commodities: &Commodities,
+) -> Result<(usize, Amount), Error> {
+    let (last_columns, last) = match parts.last() {
+        Some(last) => last,
+        None => return Err(Error::blank_entry_value()),
+    };
+
+    let last_error = match parse_value(last, DEFAULT_COMMODITY, commodities) {
+        Ok(value) => return Ok((last_columns.start, Amount::of_default(value))),
+        Err(err) => err.at_columns(last_columns.clone()),
+    };
+
+    // The value may be followed by a commodity, or by the currency symbol.
+    // Only blame the part before it when it looks like it was meant to be a
+    // value; otherwise the line is missing one altogether.
+    match parts {
+        [.., (columns, value), _] => {
+            let amount = format!("{} {}", value, last);
+            match parse_amount(amount.as_str(), commodities) {
+                Ok(amount) => Ok((columns.start, amount)),
+                Err(err) if looks_numeric(value) => {
+                    Err(err.at_columns(columns.start..last_columns.end))
+                }
+                Err(_) => Err(last_error),
+            }
+        }
+        _ => Err(last_error),
+    }
+}
+
+fn looks_numeric(part: &str) -> bool {
+    part.starts_with(|c: char| c.is_ascii_digit() || "-+(.,$€£¥₹".contains(c))
+}
+
+/// Divides, rounding halves away from zero.
+pub(crate) fn divide_rounded(dividend: i128, divisor: i128) -> i128 {
+    let quotient = dividend / divisor;
+    let remainder = dividend % divisor;
+
+    match remainder.abs() * 2 >= divisor.abs() {
+        true => quotient + dividend.signum() * divisor.signum(),
+        false => quotient,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{commodity::Commodity, currency::Currency};
+
+    use super::*;
+
+    fn commodities() -> Commodities {
+        let mut commodities = Commodities::new();
+        commodities.declare(&Commodity::try_from("2026-01-01 commodity AAPL 4").unwrap());
+        commodities
+    }
+
+    #[test]
+    fn reads_bare_integers_in_the_ledger_as_the_smallest_unit() {
+        let commodities = commodities();
+        assert_eq!(parse_value("1250", "", &commodities).unwrap(), 1250);
+        assert_eq!(parse_value("-3", "", &commodities).unwrap(), -3);
+        assert_eq!(parse_value("10", "AAPL", &commodities).unwrap(), 10);
+    }
+
+    #[test]
+    fn reads_money_notation() {
+        let commodities = commodities();
+        for (text, value) in [
+            ("12.50", 1250),
+            ("12.5", 1250),
+            ("-$1,234.00", -123400),
+            ("($1,234.56)", -123456),
+            ("+.05", 5),
+        ] {
+            assert_eq!(
+                parse_value(text, "", &commodities).unwrap(),
+                value,
+                "{}",
+                text
+            );
+        }
+        assert_eq!(parse_value("1.5", "AAPL", &commodities).unwrap(), 15000);
+    }
+
+    #[test]
+    fn reads_money_notation_in_the_home_currency_format() {
+        let mut commodities = commodities();
+        let currency = Currency::try_from("2026-01-01 currency de-DE").unwrap();
+        commodities.set_currency_format(currency.format().clone());
+
+        assert_eq!(parse_value("1.234,50 €", "", &commodities).unwrap(), 123450);
+        assert_eq!(parse_value("-1.234,5", "", &commodities).unwrap(), -123450);
+    }
+
+    #[test]
+    fn rejects_malformed_values() {
+        let commodities = commodities();
+        for text in [
+            "",
+            "12.345",
+            "1,23",
+            "1,2345.00",
+            "1.2.3",
+            "--5",
+            "(12.50",
+            "$$12.00",
+            "12.50x",
+            "99999999999999999999",
+        ] {
+            assert!(parse_value(text, "", &commodities).is_err(), "{}", text);
+        }
+        assert!(parse_value("$1.00", "AAPL", &commodities).is_err());
+    }
+
+    #[test]
+    fn reads_typed_whole_numbers_as_whole_units() {
+        let commodities = commodities();
+        assert_eq!(
+            parse_typed_amount("12", &commodities).unwrap(),
+            Amount::of_default(1200)
+        );
+        assert_eq!(
+            parse_typed_amount("-1,234", &commodities).unwrap(),
+            Amount::of_default(-123400)
+        );
+        assert_eq!(
+            parse_typed_amount("10 AAPL", &commodities).unwrap(),
+            Amount::new(100000, "AAPL")
+        );
+        assert_eq!(
+            parse_typed_amount("12.50", &commodities).unwrap(),
+            Amount::of_default(1250)
+        );
+    }
+}
@@ -0,0 +1,222 @@
+use std::collections::BTreeMap;
+
+use chrono::{Date, Utc};
+
+use crate::{
+    account::Account,
+    commodity::Commodities,
+    price::Prices,
+    transaction::{Entry, Transaction},
+};
+
+/// Account balances aggregated over any number of transactions, in any
+/// order.
+///
+/// Every entry is posted to the running total of the account it names, so an
+/// account's balance covers all of its entries no matter how far apart they
+/// are in the ledger.
+#[derive(Clone, Debug, Default)]
+pub struct Balances {
+    accounts: BTreeMap<String, Account>,
+}
+
+impl Balances {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Posts every entry of a transaction to its account.
+    pub fn post_transaction(&mut self, transaction: &Transaction) {
+        for entry in transaction.entries() {
+            self.post_entry(transaction, entry);
+        }
+    }
+
+    /// Posts a single entry of `transaction` to its account.
+    pub fn post_entry(&mut self, transaction: &Transaction, entry: &Entry) {
+        match self.accounts.get_mut(entry.account().as_str()) {
+            Some(account) => account.post(transaction.date(), entry.amount()),
+            None => {
+                let mut account = Account::new(entry.account());
+                account.post(transaction.date(), entry.amount());
+                self.accounts.insert(entry.account().to_owned(), account);
+            }
+        }
+    }
+
+    /// Keeps only the accounts for which `predicate` returns `true`.
+    pub fn retain<F: FnMut(&Account) -> bool>(&mut self, mut predicate: F) {
+        self.accounts.retain(|_, account| predicate(account));
+    }
+
+    /// Converts every account's balance into `target` at the latest prices
+    /// on or before `date`. Commodities without a price are left as they
+    /// are.
+    pub fn convert(
+        &mut self,
+        prices: &Prices,
+        target: &str,
+        date: Date<Utc>,
+        commodities: &Commodities,
+    ) {
+        for account in self.accounts.values_mut() {
+            let balance = prices.convert_all(account.balance(), target, date, commodities);
+            account.set_balance(balance);
+        }
+    }
+
+    /// Rolls every account up into its parents, so that `Assets` holds the
+    /// total of `Assets:Bank` and `Assets:Cash`, and so on.
+    ///
+    /// The result lists every parent right before its children. Accounts
+    /// deeper than `depth`, if given, are folded into their ancestor at that
+    /// depth rather than listed themselves.
+    pub fn roll_up(&self, depth: Option<usize>) -> Vec<Account> {
+        let mut tree = BTreeMap::<Vec<&str>, Account>::new();
+        for account in self.accounts.values() {
+            let segments = account.name().split(':').collect::<Vec<&str>>();
+            let depth = depth.unwrap_or(segments.len()).clamp(1, segments.len());
+
+            for level in 1..=depth {
+                tree.entry(segments[..level].to_vec())
+                    .or_insert_with(|| Account::new(segments[..level].join(":").as_str()))
+                    .merge(account);
+            }
+        }
+
+        tree.into_values().collect()
+    }
+
+    /// Folds every account deeper than `depth` into its ancestor at that
+    /// depth, without adding entries for parents that have no postings of
+    /// their own.
+    pub fn collapse(&self, depth: usize) -> Balances {
+        let mut collapsed = Balances::new();
+        for account in self.accounts.values() {
+            let name = account
+                .name()
+                .split(':')
+                .take(depth.max(1))
+                .collect::<Vec<&str>>()
+                .join(":");
+
+            collapsed
+                .accounts
+                .entry(name.clone())
+                .or_insert_with(|| Account::new(name.as_str()))
+                .merge(account);
+        }
+
+        collapsed
+    }
+
+    pub fn get(&self, account: &str) -> Option<&Account> {
+        self.accounts.get(account)
+    }
+
+    /// Every account with at least one posting, sorted by name.
+    pub fn accounts(&self) -> impl Iterator<Item = &Account> {
+        self.accounts.values()
+    }
+
+    pub fn into_accounts(self) -> Vec<Account> {
+        self.accounts.into_values().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+}
+
+impl<'t> FromIterator<&'t Transaction> for Balances {
+    fn from_iter<I: IntoIterator<Item = &'t Transaction>>(transactions: I) -> Self {
+        let mut balances = Self::new();
+        for transaction in transactions {
+            balances.post_transaction(transaction);
+        }
+
+        balances
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use ulid::Ulid;
+
+    use crate::status::Status;
+
+    use super::*;
+
+    fn transaction(day: u32, entries: &[(&str, isize)]) -> Transaction {
+        Transaction::new(
+            Utc.ymd(2026, 3, day),
+            Ulid::new(),
+            Status::default(),
+            String::new(),
+            entries
+                .iter()
+                .map(|(account, value)| Entry::new(account, *value))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn totals_non_adjacent_postings_to_the_same_account() {
+        let transactions = [
+            transaction(5, &[("Expenses:Food", 1200), ("Assets:Cash", -1200)]),
+            transaction(1, &[("Expenses:Rent", 50000), ("Assets:Bank", -50000)]),
+            transaction(9, &[("Expenses:Food", 800), ("Assets:Cash", -800)]),
+        ];
+        let balances = transactions.iter().collect::<Balances>();
+
+        let food = balances.get("Expenses:Food").unwrap();
+        assert_eq!(food.balance().get(""), 2000);
+        assert_eq!(food.postings(), 2);
+        assert_eq!(food.first_posting(), Some(Utc.ymd(2026, 3, 5)));
+        assert_eq!(food.last_posting(), Some(Utc.ymd(2026, 3, 9)));
+
+        let cash = balances.get("Assets:Cash").unwrap();
+        assert_eq!(cash.balance().get(""), -2000);
+        assert_eq!(cash.postings(), 2);
+        assert_eq!(balances.len(), 4);
+    }
+
+    #[test]
+    fn takes_first_and_last_posting_dates_regardless_of_order() {
+        let transactions = [
+            transaction(20, &[("Expenses:Food", 100), ("Assets:Cash", -100)]),
+            transaction(2, &[("Expenses:Food", 100), ("Assets:Cash", -100)]),
+            transaction(11, &[("Expenses:Food", 100), ("Assets:Cash", -100)]),
+        ];
+        let balances = transactions.iter().collect::<Balances>();
+
+        let food = balances.get("Expenses:Food").unwrap();
+        assert_eq!(food.postings(), 3);
+        assert_eq!(food.first_posting(), Some(Utc.ymd(2026, 3, 2)));
+        assert_eq!(food.last_posting(), Some(Utc.ymd(2026, 3, 20)));
+    }
+
+    #[test]
+    fn rolls_non_adjacent_children_up_into_their_parent() {
+        let transactions = [
+            transaction(1, &[("Expenses:Food", 1200), ("Assets:Cash", -1200)]),
+            transaction(2, &[("Expenses:Rent", 50000), ("Assets:Bank", -50000)]),
+            transaction(3, &[("Expenses:Food", 800), ("Assets:Cash", -800)]),
+        ];
+        let balances = transactions.iter().collect::<Balances>();
+
+        let rolled_up = balances.roll_up(Some(1));
+        let expenses = rolled_up
+            .iter()
+            .find(|account| account.name() == "Expenses")
+            .unwrap();
+        assert_eq!(expenses.balance().get(""), 52000);
+        assert_eq!(expenses.postings(), 3);
+        assert_eq!(rolled_up.len(), 2);
+    }
+}
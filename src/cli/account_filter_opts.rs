@@ -0,0 +1,25 @@
+use clap::Args;
+use sixpence::{AccountFilter, AccountPattern};
+
+#[derive(Args, Debug)]
+pub struct AccountFilterOpts {
+    /// Only include accounts matching these patterns: whole levels of an account name like
+    /// Expenses:Food or Retirement, a glob like 'Assets:*:Checking', or a regex like '^Assets'
+    #[clap(value_name = "PATTERN")]
+    patterns: Vec<AccountPattern>,
+
+    /// Leave out accounts matching these patterns
+    #[clap(
+        short = 'x',
+        long = "exclude",
+        value_name = "PATTERN",
+        multiple_occurrences(true)
+    )]
+    exclude: Vec<AccountPattern>,
+}
+
+impl AccountFilterOpts {
+    pub fn account_filter(&self) -> AccountFilter {
+        AccountFilter::new(self.patterns.clone(), self.exclude.clone())
+    }
+}
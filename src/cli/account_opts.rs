@@ -0,0 +1,71 @@
+use chrono::{Date, Utc};
+use clap::{Args, Subcommand};
+use getset::{CopyGetters, Getters};
+use sixpence::AccountType;
+
+use super::transaction_opts::parse_date;
+
+#[derive(Args, Debug, Getters)]
+pub struct AccountOpts {
+    #[getset(get = "pub")]
+    #[clap(subcommand)]
+    command: Option<AccountCommand>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AccountCommand {
+    /// List the accounts declared in the ledger, and any posted to without being declared; the default
+    List,
+
+    /// Declare an account in the ledger, open from a given date
+    Open(OpenAccountOpts),
+
+    /// Close a declared account, so nothing more can be posted to it
+    Close(CloseAccountOpts),
+}
+
+#[derive(Args, CopyGetters, Debug, Getters)]
+pub struct OpenAccountOpts {
+    /// The account's full name, e.g. "Assets:Checking"
+    #[getset(get = "pub")]
+    #[clap(value_name = "ACCOUNT")]
+    account: String,
+
+    /// What the account keeps track of: asset, liability, equity, income or expense
+    #[getset(get_copy = "pub")]
+    #[clap(short = 't', long = "type", value_name = "TYPE")]
+    account_type: Option<AccountType>,
+
+    /// Description of the account
+    #[getset(get = "pub")]
+    #[clap(short = 'd', long = "desc", value_name = "DESCRIPTION")]
+    description: Option<String>,
+
+    /// Date the account opens, defaults to today
+    #[clap(short = 'D', long = "date", value_name = "YYYY-MM-DD", parse(try_from_str = parse_date))]
+    date: Option<Date<Utc>>,
+}
+
+impl OpenAccountOpts {
+    pub fn date(&self) -> Date<Utc> {
+        self.date.unwrap_or_else(|| Utc::now().date())
+    }
+}
+
+#[derive(Args, Debug, Getters)]
+pub struct CloseAccountOpts {
+    /// The account's full name
+    #[getset(get = "pub")]
+    #[clap(value_name = "ACCOUNT")]
+    account: String,
+
+    /// The last day anything can be posted to the account, defaults to today
+    #[clap(short = 'D', long = "date", value_name = "YYYY-MM-DD", parse(try_from_str = parse_date))]
+    date: Option<Date<Utc>>,
+}
+
+impl CloseAccountOpts {
+    pub fn date(&self) -> Date<Utc> {
+        self.date.unwrap_or_else(|| Utc::now().date())
+    }
+}
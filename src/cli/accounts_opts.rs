@@ -1,10 +1,99 @@
+use chrono::{Date, Utc};
 use clap::Args;
-use getset::CopyGetters;
+use getset::{CopyGetters, Getters};
+use sixpence::{commodity::DEFAULT_COMMODITY, Status};
 
-#[derive(Args, CopyGetters, Debug)]
+use super::{transaction_opts::parse_date, AccountFilterOpts, DateRangeOpts};
+
+#[derive(Args, CopyGetters, Debug, Getters)]
 pub struct AccountsOpts {
+    #[getset(get = "pub")]
+    #[clap(flatten)]
+    account_filter: AccountFilterOpts,
+
     /// Display all accounts
     #[getset(get_copy = "pub")]
     #[clap(short = 'a', long = "all")]
     display_all: bool,
+
+    /// Only display accounts this many levels deep, rolling deeper accounts up into their parents
+    #[getset(get_copy = "pub")]
+    #[clap(long = "depth", value_name = "N", parse(try_from_str = parse_depth))]
+    depth: Option<usize>,
+
+    /// Display a flat list of accounts instead of a tree
+    #[getset(get_copy = "pub")]
+    #[clap(long = "flat")]
+    flat: bool,
+
+    #[getset(get = "pub")]
+    #[clap(flatten)]
+    date_range: DateRangeOpts,
+
+    /// Only include cleared transactions, whether reconciled or not; may be combined with the other status flags
+    #[clap(long = "cleared")]
+    cleared: bool,
+
+    /// Only include pending transactions; may be combined with the other status flags
+    #[clap(long = "pending")]
+    pending: bool,
+
+    /// Only include uncleared transactions; may be combined with the other status flags
+    #[clap(long = "uncleared")]
+    uncleared: bool,
+
+    /// Convert balances to their market value at the latest prices on or before --value=AT-DATE; defaults to the end of the date range, or today
+    #[clap(
+        long = "value",
+        value_name = "AT-DATE",
+        min_values = 0,
+        max_values = 1,
+        require_equals = true,
+        parse(try_from_str = parse_date)
+    )]
+    value: Option<Vec<Date<Utc>>>,
+
+    /// Convert balances to their market value in this commodity instead of the home currency
+    #[clap(long = "value-in", value_name = "COMMODITY")]
+    value_in: Option<String>,
+}
+
+impl AccountsOpts {
+    /// The commodity and date to value balances at, when asked to.
+    pub fn valuation(&self) -> Option<(&str, Date<Utc>)> {
+        let date = match (self.value.as_deref(), self.value_in.as_ref()) {
+            (Some([date, ..]), _) => *date,
+            (Some([]), _) | (None, Some(_)) => self
+                .date_range
+                .date_range()
+                .end()
+                .unwrap_or_else(|| Utc::now().date()),
+            (None, None) => return None,
+        };
+        let commodity = self.value_in.as_deref().unwrap_or(DEFAULT_COMMODITY);
+
+        Some((commodity, date))
+    }
+
+    /// Whether a transaction with `status` should count towards the
+    /// balances. Every status does when no status flag was given.
+    pub fn includes_status(&self, status: Status) -> bool {
+        if !(self.cleared || self.pending || self.uncleared) {
+            return true;
+        }
+
+        match status {
+            Status::Cleared | Status::Reconciled => self.cleared,
+            Status::Pending => self.pending,
+            Status::Uncleared => self.uncleared,
+        }
+    }
+}
+
+fn parse_depth(s: &str) -> Result<usize, String> {
+    match s.parse::<usize>() {
+        Ok(0) => Err("depth must be at least 1".to_owned()),
+        Ok(depth) => Ok(depth),
+        Err(err) => Err(err.to_string()),
+    }
 }
@@ -1,14 +1,40 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 
 use clap::{Args, ValueHint};
 
-use crate::error::Error;
+use sixpence::{Error, Ledger, LedgerLock, ParseMode};
 
 #[derive(Args, Debug)]
 pub struct GlobalArgs {
     /// The path to the ledger file
     #[clap(short = 'f', long = "file", value_name = "PATH", value_hint = ValueHint::DirPath, global = true)]
     ledger_file: Option<PathBuf>,
+
+    /// Report every error in the ledger file instead of stopping at the first
+    #[clap(long = "all-errors", global = true)]
+    all_errors: bool,
+
+    /// Only allow postings to accounts that have been opened in the ledger and not yet closed
+    #[clap(long = "strict", global = true)]
+    strict: bool,
+
+    /// Number of backups of the ledger file to keep when it's modified; with none, new transactions are appended in place, which a crash part way through can leave half-written, while with any every change is written atomically
+    #[clap(
+        long = "backups",
+        value_name = "COUNT",
+        default_value_t = 0,
+        global = true
+    )]
+    backups: usize,
+
+    /// Seconds to wait for another sixp process to release the ledger file
+    #[clap(
+        long = "lock-timeout",
+        value_name = "SECONDS",
+        default_value_t = 10,
+        global = true
+    )]
+    lock_timeout: u64,
 }
 
 impl GlobalArgs {
@@ -21,11 +47,52 @@ impl GlobalArgs {
             None => Ok(dirs::data_local_dir()
                 .map(|path| {
                     let mut path = path;
-					path.push(env!("CARGO_PKG_NAME"));
+                    path.push(env!("CARGO_PKG_NAME"));
                     path.push("ledger.dat");
                     path
                 })
                 .unwrap()),
         }
     }
+
+    pub fn parse_mode(&self) -> ParseMode {
+        match self.all_errors {
+            true => ParseMode::CollectAll,
+            false => ParseMode::FailFast,
+        }
+    }
+
+    pub fn strict(&self) -> bool {
+        self.strict
+    }
+
+    pub fn backups(&self) -> usize {
+        self.backups
+    }
+
+    pub fn open_ledger(&self) -> Result<Ledger, Error> {
+        Ledger::open_with(self.ledger_file()?, self.parse_mode())
+    }
+
+    /// Opens the ledger for a command that modifies it, holding the ledger's
+    /// lock until the returned `Ledger` is dropped.
+    pub fn open_ledger_for_writing(&self) -> Result<Ledger, Error> {
+        let mut ledger = Ledger::open_locked(
+            self.ledger_file()?,
+            self.parse_mode(),
+            Duration::from_secs(self.lock_timeout),
+        )?;
+        ledger.set_backups(self.backups);
+
+        Ok(ledger)
+    }
+
+    /// Locks the ledger file for a command that modifies it without loading
+    /// it into memory first.
+    pub fn lock_ledger(&self) -> Result<LedgerLock, Error> {
+        LedgerLock::acquire(
+            self.ledger_file()?.as_path(),
+            Duration::from_secs(self.lock_timeout),
+        )
+    }
 }
@@ -0,0 +1,12 @@
+use std::path::PathBuf;
+
+use clap::{Args, ValueHint};
+use getset::Getters;
+
+#[derive(Args, Debug, Getters)]
+pub struct CheckOpts {
+    /// File listing the declared accounts, one per line; every account posted to must be among them
+    #[getset(get = "pub")]
+    #[clap(long = "accounts", value_name = "PATH", value_hint = ValueHint::FilePath)]
+    accounts: Option<PathBuf>,
+}
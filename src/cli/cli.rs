@@ -1,6 +1,10 @@
 use clap::{Parser, Subcommand};
 
-use super::{args::GlobalArgs, transaction_opts::TransactionOpts, AccountsOpts};
+use super::{
+    args::GlobalArgs, transaction_opts::TransactionOpts, AccountOpts, AccountsOpts, CheckOpts,
+    ConvertOpts, EditOpts, LotsOpts, NewOpts, PricesOpts, ReconcileOpts, RegisterOpts, RemoveOpts,
+    ShowOpts, VoidOpts,
+};
 
 #[derive(Debug, Parser)]
 #[clap(about, author, version)]
@@ -25,12 +29,50 @@ impl Cli {
 #[derive(Debug, Subcommand)]
 pub enum Commands {
     /// Create a new, empty ledger
-    New,
+    New(NewOpts),
+
+    /// Rewrite the ledger file in the text or binary format
+    Convert(ConvertOpts),
+
+    /// Check the ledger file for problems, listing every one found
+    Check(CheckOpts),
+
+    /// Upgrade the ledger file to the current version of its format, keeping a backup
+    Migrate,
 
     /// Display accounts and their positions from the ledger
     #[clap(name = "accts")]
     Accounts(AccountsOpts),
 
+    /// Declare, close or list the accounts in the ledger
+    Account(AccountOpts),
+
+    /// List postings to accounts along with a running balance
+    #[clap(name = "reg")]
+    Register(RegisterOpts),
+
+    /// Display a single transaction from the ledger
+    Show(ShowOpts),
+
+    /// Change an existing transaction in the ledger
+    Edit(EditOpts),
+
+    /// Delete a transaction from the ledger
+    #[clap(name = "rm")]
+    Remove(RemoveOpts),
+
+    /// Cancel out a transaction by adding a reversing one to the ledger
+    Void(VoidOpts),
+
+    /// List or record commodity prices
+    Prices(PricesOpts),
+
+    /// List the lots still held in investment accounts, with what they cost
+    Lots(LotsOpts),
+
+    /// Reconcile an account against a bank statement
+    Reconcile(ReconcileOpts),
+
     /// Create new transactions and add them to the ledger
     #[clap(name = "txn")]
     Transaction(TransactionOpts),
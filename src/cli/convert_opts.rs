@@ -0,0 +1,18 @@
+use std::path::PathBuf;
+
+use clap::{Args, ValueHint};
+use getset::{CopyGetters, Getters};
+use sixpence::LedgerFormat;
+
+#[derive(Args, CopyGetters, Debug, Getters)]
+pub struct ConvertOpts {
+    /// Format to convert the ledger to, text or binary
+    #[getset(get_copy = "pub")]
+    #[clap(long = "to", value_name = "FORMAT")]
+    to: LedgerFormat,
+
+    /// Write the converted ledger to a new file instead of replacing the ledger file
+    #[getset(get = "pub")]
+    #[clap(short = 'o', long = "output", value_name = "PATH", value_hint = ValueHint::FilePath)]
+    output: Option<PathBuf>,
+}
@@ -0,0 +1,28 @@
+use chrono::{Date, Utc};
+use clap::Args;
+use sixpence::DateRange;
+
+use super::transaction_opts::parse_date;
+
+#[derive(Args, Debug, Default)]
+pub struct DateRangeOpts {
+    /// Only include transactions on or after this date
+    #[clap(short = 'b', long = "begin", value_name = "YYYY-MM-DD", parse(try_from_str = parse_date))]
+    begin: Option<Date<Utc>>,
+
+    /// Only include transactions on or before this date
+    #[clap(short = 'e', long = "end", value_name = "YYYY-MM-DD", parse(try_from_str = parse_date))]
+    end: Option<Date<Utc>>,
+
+    /// Only include transactions within a period, like 2026, 2026-Q3, 2026-09, 'last month' or ytd
+    #[clap(short = 'p', long = "period", value_name = "PERIOD")]
+    period: Option<DateRange>,
+}
+
+impl DateRangeOpts {
+    pub fn date_range(&self) -> DateRange {
+        self.period
+            .unwrap_or_default()
+            .intersect(&DateRange::new(self.begin, self.end))
+    }
+}
@@ -0,0 +1,59 @@
+use chrono::{Date, Utc};
+use clap::Args;
+use getset::{CopyGetters, Getters};
+use sixpence::{Commodities, DraftEntry, Error, LotMethod, Status};
+
+use super::transaction_opts::{draft_entries, parse_date, parse_entries, EntryArg};
+
+#[derive(Args, CopyGetters, Debug, Getters)]
+pub struct EditOpts {
+    /// The transaction's id, or enough of the start of it to be unique
+    #[getset(get = "pub")]
+    #[clap(value_name = "ID")]
+    id: String,
+
+    /// Change the date of the transaction
+    #[getset(get_copy = "pub")]
+    #[clap(short = 'D', long = "date", value_name = "YYYY-MM-DD", parse(try_from_str = parse_date))]
+    date: Option<Date<Utc>>,
+
+    /// Change the status of the transaction: uncleared, pending, cleared or reconciled
+    #[getset(get_copy = "pub")]
+    #[clap(short = 's', long = "status", value_name = "STATUS")]
+    status: Option<Status>,
+
+    /// Change the description of the transaction
+    #[getset(get = "pub")]
+    #[clap(short = 'd', long = "desc", value_name = "DESCRIPTION")]
+    description: Option<String>,
+
+    /// Replace the transaction's entries; a value may be followed by its commodity, e.g. "Assets:Euro=12.50 EUR", and a per-unit cost or price as with `txn`; only one entry may leave out its value
+    #[clap(value_name = "ACCOUNT[=VALUE]", parse(try_from_str = parse_entries), multiple_occurrences(true))]
+    entries: Vec<EntryArg>,
+
+    /// Which lots sales draw down first, fifo or lifo, among those an entry's cost and lot date allow
+    #[getset(get_copy = "pub")]
+    #[clap(long = "lot-method", value_name = "METHOD", default_value_t)]
+    lot_method: LotMethod,
+
+    /// Edit the transaction in $VISUAL or $EDITOR instead
+    #[getset(get_copy = "pub")]
+    #[clap(
+        short = 'e',
+        long = "editor",
+        conflicts_with_all = &["date", "status", "description", "entries"]
+    )]
+    use_editor: bool,
+}
+
+impl EditOpts {
+    pub fn has_entries(&self) -> bool {
+        !self.entries.is_empty()
+    }
+
+    /// The replacement entries, with their amounts read in the ledger's
+    /// currency format and the precision of their commodities.
+    pub fn entries(&self, commodities: &Commodities) -> Result<Vec<DraftEntry>, Error> {
+        draft_entries(self.entries.as_slice(), commodities)
+    }
+}
@@ -0,0 +1,11 @@
+use clap::Args;
+use getset::Getters;
+
+use super::AccountFilterOpts;
+
+#[derive(Args, Debug, Getters)]
+pub struct LotsOpts {
+    #[getset(get = "pub")]
+    #[clap(flatten)]
+    account_filter: AccountFilterOpts,
+}
@@ -1,8 +1,38 @@
+mod account_filter_opts;
+mod account_opts;
 mod accounts_opts;
 mod args;
+mod check_opts;
+#[allow(clippy::module_inception)]
 mod cli;
+mod convert_opts;
+mod date_range_opts;
+mod edit_opts;
+mod lots_opts;
+mod new_opts;
+mod prices_opts;
+mod reconcile_opts;
+mod register_opts;
+mod remove_opts;
+mod show_opts;
 mod transaction_opts;
+mod void_opts;
 
+pub use account_filter_opts::AccountFilterOpts;
+pub use account_opts::{AccountCommand, AccountOpts, CloseAccountOpts, OpenAccountOpts};
 pub use accounts_opts::AccountsOpts;
+pub use args::GlobalArgs;
+pub use check_opts::CheckOpts;
 pub use cli::{Cli, Commands};
+pub use convert_opts::ConvertOpts;
+pub use date_range_opts::DateRangeOpts;
+pub use edit_opts::EditOpts;
+pub use lots_opts::LotsOpts;
+pub use new_opts::NewOpts;
+pub use prices_opts::{AddPriceOpts, ListPricesOpts, PricesCommand, PricesOpts};
+pub use reconcile_opts::ReconcileOpts;
+pub use register_opts::RegisterOpts;
+pub use remove_opts::RemoveOpts;
+pub use show_opts::ShowOpts;
 pub use transaction_opts::TransactionOpts;
+pub use void_opts::VoidOpts;
@@ -0,0 +1,11 @@
+use clap::Args;
+use getset::CopyGetters;
+use sixpence::LedgerFormat;
+
+#[derive(Args, CopyGetters, Debug)]
+pub struct NewOpts {
+    /// Format to store the ledger in, text or binary; defaults to binary for a file ending in .bin and text otherwise
+    #[getset(get_copy = "pub")]
+    #[clap(long = "format", value_name = "FORMAT")]
+    format: Option<LedgerFormat>,
+}
@@ -0,0 +1,60 @@
+use chrono::{Date, Utc};
+use clap::{Args, Subcommand};
+use getset::Getters;
+use sixpence::{amount::parse_typed_amount, Amount, Commodities, Error};
+
+use super::{transaction_opts::parse_date, DateRangeOpts};
+
+#[derive(Args, Debug, Getters)]
+pub struct PricesOpts {
+    #[getset(get = "pub")]
+    #[clap(subcommand)]
+    command: Option<PricesCommand>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum PricesCommand {
+    /// List the prices recorded in the ledger; the default
+    List(ListPricesOpts),
+
+    /// Record the price of a commodity in the ledger
+    Add(AddPriceOpts),
+}
+
+#[derive(Args, Debug, Default, Getters)]
+pub struct ListPricesOpts {
+    /// Only list prices of this commodity
+    #[getset(get = "pub")]
+    #[clap(value_name = "COMMODITY")]
+    commodity: Option<String>,
+
+    #[getset(get = "pub")]
+    #[clap(flatten)]
+    date_range: DateRangeOpts,
+}
+
+#[derive(Args, Debug, Getters)]
+pub struct AddPriceOpts {
+    /// The commodity being priced
+    #[getset(get = "pub")]
+    #[clap(value_name = "COMMODITY")]
+    commodity: String,
+
+    /// What one unit of the commodity is worth, in the home currency or the commodity that follows it, e.g. "227.50" or "1.05 USD"
+    #[clap(value_name = "PRICE")]
+    price: String,
+
+    /// Date of the price, defaults to today
+    #[clap(short = 'D', long = "date", value_name = "YYYY-MM-DD", parse(try_from_str = parse_date))]
+    date: Option<Date<Utc>>,
+}
+
+impl AddPriceOpts {
+    pub fn date(&self) -> Date<Utc> {
+        self.date.unwrap_or_else(|| Utc::now().date())
+    }
+
+    pub fn price(&self, commodities: &Commodities) -> Result<Amount, Error> {
+        parse_typed_amount(self.price.as_str(), commodities)
+    }
+}
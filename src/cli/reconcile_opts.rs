@@ -0,0 +1,33 @@
+use chrono::{Date, Utc};
+use clap::Args;
+use getset::{CopyGetters, Getters};
+use sixpence::{amount::parse_typed_amount, Amount, Commodities, Error};
+
+use super::transaction_opts::parse_date;
+
+#[derive(Args, CopyGetters, Debug, Getters)]
+pub struct ReconcileOpts {
+    /// The account to reconcile
+    #[getset(get = "pub")]
+    #[clap(value_name = "ACCOUNT")]
+    account: String,
+
+    /// Closing date of the statement; later postings are left out
+    #[getset(get_copy = "pub")]
+    #[clap(long = "statement-date", value_name = "YYYY-MM-DD", parse(try_from_str = parse_date))]
+    statement_date: Date<Utc>,
+
+    /// Closing balance of the statement, optionally followed by its commodity, e.g. "1,234.56" or "-$12.00"
+    #[clap(
+        long = "statement-balance",
+        value_name = "VALUE",
+        allow_hyphen_values = true
+    )]
+    statement_balance: String,
+}
+
+impl ReconcileOpts {
+    pub fn statement_balance(&self, commodities: &Commodities) -> Result<Amount, Error> {
+        parse_typed_amount(self.statement_balance.as_str(), commodities)
+    }
+}
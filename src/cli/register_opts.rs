@@ -0,0 +1,25 @@
+use clap::Args;
+use getset::{CopyGetters, Getters};
+
+use super::{AccountFilterOpts, DateRangeOpts};
+
+#[derive(Args, CopyGetters, Debug, Getters)]
+pub struct RegisterOpts {
+    #[getset(get = "pub")]
+    #[clap(flatten)]
+    account_filter: AccountFilterOpts,
+
+    #[getset(get = "pub")]
+    #[clap(flatten)]
+    date_range: DateRangeOpts,
+
+    /// List the newest postings first
+    #[getset(get_copy = "pub")]
+    #[clap(short = 'r', long = "reverse")]
+    reverse: bool,
+
+    /// Only list this many of the most recent postings
+    #[getset(get_copy = "pub")]
+    #[clap(short = 'l', long = "limit", value_name = "N")]
+    limit: Option<usize>,
+}
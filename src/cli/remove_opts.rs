@@ -0,0 +1,15 @@
+use clap::Args;
+use getset::{CopyGetters, Getters};
+
+#[derive(Args, CopyGetters, Debug, Getters)]
+pub struct RemoveOpts {
+    /// The transaction's id, or enough of the start of it to be unique
+    #[getset(get = "pub")]
+    #[clap(value_name = "ID")]
+    id: String,
+
+    /// Don't ask for confirmation before deleting the transaction
+    #[getset(get_copy = "pub")]
+    #[clap(short = 'y', long = "yes")]
+    assume_yes: bool,
+}
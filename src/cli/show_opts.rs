@@ -0,0 +1,10 @@
+use clap::Args;
+use getset::Getters;
+
+#[derive(Args, Debug, Getters)]
+pub struct ShowOpts {
+    /// The transaction's id, or enough of the start of it to be unique
+    #[getset(get = "pub")]
+    #[clap(value_name = "ID")]
+    id: String,
+}
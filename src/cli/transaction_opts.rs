@@ -1,9 +1,11 @@
 use chrono::{Date, NaiveDate, TimeZone, Utc};
 use clap::Args;
 use getset::{CopyGetters, Getters};
-use itertools::Itertools;
-
-use crate::error::Error;
+use sixpence::{
+    amount::parse_typed_amount, transaction::validate_and_normalize_entries, Commodities,
+    DraftEntry, Error, LotMethod, Lots, Status, Transaction,
+};
+use ulid::Ulid;
 
 #[derive(Args, CopyGetters, Debug, Getters)]
 pub struct TransactionOpts {
@@ -11,20 +13,28 @@ pub struct TransactionOpts {
     #[clap(short = 'D', long = "date", value_name = "YYYY-MM-DD", parse(try_from_str = parse_date))]
     date: Option<Date<Utc>>,
 
-    /// Mark transaction as not having cleared through the bank
+    /// Mark transaction as not having cleared through the bank; the same as `--status uncleared`
     #[getset(get_copy = "pub")]
-    #[clap(short = 'n', long = "not-cleared")]
+    #[clap(short = 'n', long = "not-cleared", conflicts_with = "status")]
     has_not_cleared: bool,
 
+    /// Status of the transaction: uncleared, pending, cleared or reconciled
+    #[clap(short = 's', long = "status", value_name = "STATUS")]
+    status: Option<Status>,
+
     /// Description of the transaction
     #[getset(get = "pub")]
     #[clap(short = 'd', long = "desc", value_name = "DESCRIPTION")]
     description: Option<String>,
 
-    /// Transaction entries; only one entry may leave out its value
-    #[getset(get = "pub")]
+    /// Which lots sales draw down first, fifo or lifo, among those an entry's cost and lot date allow
+    #[getset(get_copy = "pub")]
+    #[clap(long = "lot-method", value_name = "METHOD", default_value_t)]
+    lot_method: LotMethod,
+
+    /// Transaction entries; a value like "12.50", "-$1,234.00" or "(12.50)" is in the home currency unless followed by a commodity, e.g. "Assets:Euro=12.50 EUR", and a whole number like "12" is whole units, the same as "12.00"; a value may have a per-unit price, e.g. "Assets:Brokerage=10 AAPL @ 150.00 USD", or on a sale the cost or purchase date of the lot to sell from, or both, e.g. "Assets:Brokerage=-5 AAPL {150.00 USD} @ 175.00 USD", "{2026-01-05}" or "{150.00 USD, 2026-01-05}"; only one entry may leave out its value
     #[clap(value_name = "ACCOUNT[=VALUE]", parse(try_from_str = parse_entries), multiple_occurrences(true))]
-    entries: Vec<(String, Option<isize>)>,
+    entries: Vec<EntryArg>,
 }
 
 impl TransactionOpts {
@@ -33,9 +43,45 @@ impl TransactionOpts {
             .map(|date| Utc.from_local_date(&date.naive_local()).unwrap())
             .unwrap_or(Utc::now().date())
     }
+
+    pub fn status(&self) -> Status {
+        match self.has_not_cleared {
+            true => Status::Uncleared,
+            false => self.status.unwrap_or_default(),
+        }
+    }
+
+    /// Whether reading the entries takes the ledger's commodities and lots,
+    /// which it doesn't when every value is a whole number.
+    pub fn needs_ledger(&self) -> bool {
+        self.entries.iter().any(|entry| !entry.is_plain())
+    }
+
+    /// Builds the transaction, matching any sales against `lots`.
+    pub fn transaction(
+        &self,
+        lots: &Lots,
+        commodities: &Commodities,
+    ) -> Result<Transaction, Error> {
+        if self.entries.is_empty() {
+            return Err(Error::missing_transaction_entries());
+        }
+
+        let entries = draft_entries(self.entries.as_slice(), commodities)?;
+        let entries = lots.book(entries.as_slice(), self.lot_method)?;
+        let entries = validate_and_normalize_entries(entries.as_slice(), commodities)?;
+
+        Ok(Transaction::new(
+            self.date(),
+            Ulid::new(),
+            self.status(),
+            self.description.to_owned().unwrap_or_default(),
+            entries,
+        ))
+    }
 }
 
-fn parse_date(s: &str) -> Result<Date<Utc>, Error> {
+pub(super) fn parse_date(s: &str) -> Result<Date<Utc>, Error> {
     let date = Utc
         .from_local_date(&NaiveDate::parse_from_str(s, "%Y-%m-%d")?)
         .unwrap();
@@ -43,28 +89,158 @@ fn parse_date(s: &str) -> Result<Date<Utc>, Error> {
     Ok(date)
 }
 
-fn parse_entries(s: &str) -> Result<(String, Option<isize>), Error> {
-    match s.find("=") {
-        Some(_) => {
-            let (account, value) = s
-                .split("=")
-                .map(|value| value.split(".").join(""))
-                .enumerate()
-                .fold((String::new(), String::new()), |tup, (idx, cur)| {
-                    let (account, value) = tup;
-                    if idx == 0 {
-                        (cur, value)
-                    } else {
-                        (account, cur)
-                    }
-                });
-
-            let value = value
-                .parse::<isize>()
-                .map_err(|_| Error::invalid_entry_format(s))?;
-
-            Ok((account, Some(value)))
+/// An entry as typed on the command line, with its amounts kept as written
+/// until the ledger's currency format and commodities are known.
+#[derive(Clone, Debug)]
+pub struct EntryArg {
+    account: String,
+    amount: Option<String>,
+    cost: Option<String>,
+    price: Option<String>,
+}
+
+impl EntryArg {
+    /// Whether the entry's value is a whole number of the home currency,
+    /// which reads the same whatever the ledger declares.
+    fn is_plain(&self) -> bool {
+        self.cost.is_none()
+            && self.price.is_none()
+            && self.amount.as_ref().is_none_or(|amount| {
+                let digits = amount.trim();
+                let digits = digits.strip_prefix(['-', '+']).unwrap_or(digits);
+
+                !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+            })
+    }
+
+    fn draft(&self, commodities: &Commodities) -> Result<DraftEntry, Error> {
+        let parse = |text: &Option<String>| {
+            text.as_ref()
+                .map(|text| parse_typed_amount(text.as_str(), commodities))
+                .transpose()
+        };
+
+        let (cost, lot_date) = match self.cost.as_deref().map(split_lot_date) {
+            Some((cost, lot_date)) => (cost.map(str::to_owned), lot_date),
+            None => (None, None),
+        };
+
+        Ok(DraftEntry::new(
+            self.account.as_str(),
+            parse(&self.amount)?,
+            parse(&cost)?,
+            parse(&self.price)?,
+        )
+        .with_lot_date(lot_date))
+    }
+}
+
+/// Splits what's between an entry's braces into the cost and purchase date
+/// of the lot it sells from, either of which may be left out. Costs can have
+/// commas of their own, so only a date after the last comma is taken as one.
+fn split_lot_date(text: &str) -> (Option<&str>, Option<Date<Utc>>) {
+    if let Ok(date) = parse_date(text.trim()) {
+        return (None, Some(date));
+    }
+
+    match text.rsplit_once(',') {
+        Some((cost, date)) => match parse_date(date.trim()) {
+            Ok(date) => (Some(cost.trim()), Some(date)),
+            Err(_) => (Some(text), None),
+        },
+        None => (Some(text), None),
+    }
+}
+
+pub(super) fn draft_entries(
+    entries: &[EntryArg],
+    commodities: &Commodities,
+) -> Result<Vec<DraftEntry>, Error> {
+    entries
+        .iter()
+        .map(|entry| entry.draft(commodities))
+        .collect()
+}
+
+/// Parses an entry in `ACCOUNT[=VALUE [{COST}] [@ PRICE]]` format, leaving
+/// the amounts to be read later.
+pub(super) fn parse_entries(s: &str) -> Result<EntryArg, Error> {
+    let (account, rest) = match s.split_once('=') {
+        Some(split) => split,
+        None if s.trim().is_empty() => return Err(Error::empty_account_name()),
+        None => {
+            return Ok(EntryArg {
+                account: s.to_owned(),
+                amount: None,
+                cost: None,
+                price: None,
+            })
+        }
+    };
+    if account.trim().is_empty() {
+        return Err(Error::empty_account_name());
+    }
+
+    let text = |part: &str| match part.trim() {
+        "" => Err(Error::invalid_entry_format(s)),
+        part => Ok(part.to_owned()),
+    };
+
+    let (rest, price) = match rest.rsplit_once('@') {
+        Some((rest, price)) => (rest, Some(text(price)?)),
+        None => (rest, None),
+    };
+    let (rest, cost) = match rest.trim_end().strip_suffix('}') {
+        Some(rest) => match rest.rsplit_once('{') {
+            Some((rest, cost)) => (rest, Some(text(cost)?)),
+            None => return Err(Error::invalid_entry_format(s)),
+        },
+        None => (rest, None),
+    };
+
+    Ok(EntryArg {
+        account: account.to_owned(),
+        amount: Some(text(rest)?),
+        cost,
+        price,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use sixpence::error::ErrorKind;
+
+    use super::*;
+
+    #[test]
+    fn rejects_entries_without_an_account() {
+        for entry in ["=5", " =5", "", " "] {
+            let err = parse_entries(entry).unwrap_err();
+            assert!(
+                matches!(err.kind(), ErrorKind::EmptyAccountName),
+                "{}",
+                entry
+            );
         }
-        None => Ok((s.to_string(), None)),
+    }
+
+    #[test]
+    fn keeps_amounts_as_written() {
+        let entry = parse_entries("Assets:Brokerage=-5 AAPL {150.00} @ 175.00").unwrap();
+        assert_eq!(entry.account, "Assets:Brokerage");
+        assert_eq!(entry.amount.as_deref(), Some("-5 AAPL"));
+        assert_eq!(entry.cost.as_deref(), Some("150.00"));
+        assert_eq!(entry.price.as_deref(), Some("175.00"));
+    }
+
+    #[test]
+    fn splits_the_lot_date_from_the_cost() {
+        let date = Some(Utc.ymd(2026, 1, 5));
+        assert_eq!(split_lot_date("2026-01-05"), (None, date));
+        assert_eq!(
+            split_lot_date("150.00 USD, 2026-01-05"),
+            (Some("150.00 USD"), date)
+        );
+        assert_eq!(split_lot_date("1,234.00"), (Some("1,234.00"), None));
     }
 }
@@ -0,0 +1,23 @@
+use chrono::{Date, Utc};
+use clap::Args;
+use getset::{CopyGetters, Getters};
+
+use super::transaction_opts::parse_date;
+
+#[derive(Args, CopyGetters, Debug, Getters)]
+pub struct VoidOpts {
+    /// The transaction's id, or enough of the start of it to be unique
+    #[getset(get = "pub")]
+    #[clap(value_name = "ID")]
+    id: String,
+
+    /// Date of the reversing transaction, defaults to today
+    #[getset(get_copy = "pub")]
+    #[clap(short = 'D', long = "date", value_name = "YYYY-MM-DD", parse(try_from_str = parse_date))]
+    date: Option<Date<Utc>>,
+
+    /// Don't ask for confirmation before voiding the transaction
+    #[getset(get_copy = "pub")]
+    #[clap(short = 'y', long = "yes")]
+    assume_yes: bool,
+}
@@ -0,0 +1,139 @@
+use std::collections::BTreeSet;
+
+use sixpence::{AccountClosing, AccountOpening, Directive, Error};
+
+use crate::cli::{AccountCommand, AccountOpts, CloseAccountOpts, GlobalArgs, OpenAccountOpts};
+
+pub fn account(global_args: &GlobalArgs, opts: &AccountOpts) -> Result<(), Error> {
+    match opts.command() {
+        Some(AccountCommand::Open(opts)) => open_account(global_args, opts),
+        Some(AccountCommand::Close(opts)) => close_account(global_args, opts),
+        Some(AccountCommand::List) | None => list_accounts(global_args),
+    }
+}
+
+fn list_accounts(global_args: &GlobalArgs) -> Result<(), Error> {
+    let ledger = global_args.open_ledger()?;
+    let declarations = ledger.account_declarations();
+
+    let mut rows = declarations
+        .iter()
+        .map(|declaration| {
+            let opened = declaration.opened().format("%Y-%m-%d").to_string();
+            let open = match declaration.closed() {
+                Some(closed) => format!("{} to {}", opened, closed.format("%Y-%m-%d")),
+                None => format!("since {}", opened),
+            };
+
+            [
+                declaration.name().to_owned(),
+                declaration
+                    .account_type()
+                    .map(|account_type| account_type.to_string())
+                    .unwrap_or_default(),
+                open,
+                declaration.description().to_owned(),
+            ]
+        })
+        .collect::<Vec<[String; 4]>>();
+
+    // Accounts posted to without being declared are listed too, since
+    // they're usually typos.
+    let undeclared = ledger
+        .transactions()
+        .iter()
+        .flat_map(|transaction| transaction.entries().iter())
+        .map(|entry| entry.account())
+        .filter(|account| declarations.get(account).is_none())
+        .collect::<BTreeSet<&String>>();
+    rows.extend(undeclared.into_iter().map(|account| {
+        [
+            account.to_owned(),
+            String::new(),
+            "undeclared".to_owned(),
+            String::new(),
+        ]
+    }));
+
+    if rows.is_empty() {
+        println!("No accounts found in the ledger.");
+        return Ok(());
+    }
+
+    let widths = rows.iter().fold([0; 3], |widths, row| {
+        [
+            widths[0].max(row[0].chars().count()),
+            widths[1].max(row[1].chars().count()),
+            widths[2].max(row[2].chars().count()),
+        ]
+    });
+
+    for [name, account_type, open, description] in rows.iter() {
+        let line = format!(
+            "{:<name_width$}  {:<type_width$}  {:<open_width$}  {}",
+            name,
+            account_type,
+            open,
+            description,
+            name_width = widths[0],
+            type_width = widths[1],
+            open_width = widths[2]
+        );
+        println!("{}", line.trim_end());
+    }
+
+    Ok(())
+}
+
+fn open_account(global_args: &GlobalArgs, opts: &OpenAccountOpts) -> Result<(), Error> {
+    let mut ledger = global_args.open_ledger_for_writing()?;
+    let opening = AccountOpening::new(
+        opts.date(),
+        opts.account(),
+        opts.account_type(),
+        opts.description().as_deref().unwrap_or_default(),
+    )?;
+    if ledger
+        .account_declarations()
+        .get(opening.account())
+        .is_some()
+    {
+        return Err(Error::account_already_declared(opening.account()));
+    }
+
+    let account = opening.account().to_owned();
+    ledger.add_directive(Directive::AccountOpening(opening));
+    ledger.save()?;
+
+    println!(
+        "Account {} opened on {}.",
+        account,
+        opts.date().format("%Y-%m-%d")
+    );
+
+    Ok(())
+}
+
+fn close_account(global_args: &GlobalArgs, opts: &CloseAccountOpts) -> Result<(), Error> {
+    let mut ledger = global_args.open_ledger_for_writing()?;
+    let closing = AccountClosing::new(opts.date(), opts.account())?;
+    match ledger.account_declarations().get(closing.account()) {
+        None => return Err(Error::undeclared_account(closing.account())),
+        Some(declaration) if !declaration.is_open_on(closing.date()) => {
+            return Err(Error::account_not_open(closing.account(), closing.date()))
+        }
+        Some(_) => {}
+    }
+
+    let account = closing.account().to_owned();
+    ledger.add_directive(Directive::AccountClosing(closing));
+    ledger.save()?;
+
+    println!(
+        "Account {} closed after {}.",
+        account,
+        opts.date().format("%Y-%m-%d")
+    );
+
+    Ok(())
+}
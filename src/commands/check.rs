@@ -0,0 +1,38 @@
+use std::{collections::BTreeSet, fs, path::Path};
+
+use sixpence::{services::check_ledger, Error};
+
+use crate::cli::{CheckOpts, GlobalArgs};
+
+pub fn check(global_args: &GlobalArgs, opts: &CheckOpts) -> Result<(), Error> {
+    let ledger_file_path = global_args.ledger_file()?;
+    let declared_accounts = opts
+        .accounts()
+        .as_deref()
+        .map(read_account_list)
+        .transpose()?;
+
+    let mut findings = check_ledger(
+        ledger_file_path.as_path(),
+        declared_accounts.as_ref(),
+        global_args.strict(),
+    )?;
+    match findings.len() {
+        0 => {
+            println!("No problems found in {}.", ledger_file_path.display());
+            Ok(())
+        }
+        1 => Err(findings.remove(0)),
+        _ => Err(Error::multiple(findings)),
+    }
+}
+
+/// Reads a list of account names, one per line. Blank lines are skipped.
+fn read_account_list(path: &Path) -> Result<BTreeSet<String>, Error> {
+    Ok(fs::read_to_string(path)?
+        .lines()
+        .map(str::trim)
+        .filter(|account| !account.is_empty())
+        .map(str::to_owned)
+        .collect())
+}
@@ -0,0 +1,15 @@
+use std::io::{self, BufRead, Write};
+
+use sixpence::Error;
+
+/// Asks the user a yes/no question on the terminal. Anything other than an
+/// explicit yes, including end of input, counts as a no.
+pub fn confirm(question: &str) -> Result<bool, Error> {
+    print!("{} [y/N] ", question);
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
@@ -0,0 +1,46 @@
+use std::io;
+
+use sixpence::Error;
+
+use crate::cli::{ConvertOpts, GlobalArgs};
+
+pub fn convert(global_args: &GlobalArgs, opts: &ConvertOpts) -> Result<(), Error> {
+    let format = opts.to();
+
+    if let Some(output) = opts.output() {
+        if output.exists() {
+            return Err(Error::io(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} already exists", output.display()),
+            )));
+        }
+
+        let ledger = global_args.open_ledger()?;
+        format.write(
+            output.as_path(),
+            ledger.transactions(),
+            ledger.directives(),
+            0,
+        )?;
+
+        println!(
+            "Ledger written in the {} format to: {}",
+            format,
+            output.display()
+        );
+        return Ok(());
+    }
+
+    let mut ledger = global_args.open_ledger_for_writing()?;
+    if ledger.format() == format {
+        println!("Ledger file is already in the {} format.", format);
+        return Ok(());
+    }
+
+    ledger.set_format(format);
+    ledger.save()?;
+
+    println!("Ledger file converted to the {} format.", format);
+
+    Ok(())
+}
@@ -1,68 +1,120 @@
-use std::path::{Path, PathBuf};
+use sixpence::{Account, Balances, Commodities, Error};
 
-use crate::{account::Account, cli::AccountsOpts, error::Error, services::read_transactions};
+use crate::cli::{AccountsOpts, GlobalArgs};
 
-use super::reconcile_accounts::reconcile_accounts;
-
-pub fn command<'p>(ledger_file_path: &'p Path, opts: &AccountsOpts) -> Result<(), Error> {
-    if !ledger_file_path.exists() {
-        return Err(Error::ledger_file_not_found(PathBuf::from(
-            ledger_file_path,
-        )));
+pub fn command(global_args: &GlobalArgs, opts: &AccountsOpts) -> Result<(), Error> {
+    let ledger = global_args.open_ledger()?;
+    if ledger.transactions().is_empty() {
+        println!("No transactions found in the ledger.");
+        return Ok(());
     }
 
-    let transactions = read_transactions(ledger_file_path)?;
-    if transactions.is_empty() {
-        println!("No transactions found in the ledger.");
+    let date_range = opts.date_range().date_range();
+    let mut balances = ledger
+        .transactions_in(date_range)
+        .filter(|transaction| opts.includes_status(transaction.status()))
+        .collect::<Balances>();
+    filter_accounts(&mut balances, opts);
+    if balances.is_empty() {
+        println!("No matching accounts found in the ledger.");
         return Ok(());
     }
 
-    let accounts = filter_accounts(reconcile_accounts(transactions.as_slice()), opts);
+    let commodities = ledger.commodities();
+    if let Some((commodity, date)) = opts.valuation() {
+        balances.convert(&ledger.prices(), commodity, date, &commodities);
+    }
+
+    let rows = if opts.flat() {
+        flat_rows(&balances, opts.depth(), &commodities)
+    } else {
+        tree_rows(balances.roll_up(opts.depth()).as_slice(), &commodities)
+    };
 
-    let (longest_account_length, longest_value_length) = accounts
+    let (longest_account_length, longest_value_length) = rows
         .iter()
-        .map(|account| {
-            (
-                account.name().len(),
-                account.format_value_as_currency().len(),
-            )
-        })
+        .map(|(label, value)| (label.chars().count(), value.chars().count()))
         .fold((0, 0), |(account_a, amount_a), (account_b, amount_b)| {
-            let account = if account_a >= account_b {
-                account_a
-            } else {
-                account_b
-            };
-
-            let amount = if amount_a >= amount_b {
-                amount_a
-            } else {
-                amount_b
-            };
-
-            (account, amount)
+            (account_a.max(account_b), amount_a.max(amount_b))
         });
 
-    accounts.iter().for_each(|account| {
-        println!(
-            "{:.<first_width$}{:^second_width$}",
-            account.name(),
-            account.format_value_as_currency(),
-            first_width = longest_account_length + 4,
-            second_width = longest_value_length
-        )
-    });
+    rows.iter()
+        .for_each(|(label, value)| match label.is_empty() {
+            true => println!(
+                "{:<first_width$}{:^second_width$}",
+                label,
+                value,
+                first_width = longest_account_length + 4,
+                second_width = longest_value_length
+            ),
+            false => println!(
+                "{:.<first_width$}{:^second_width$}",
+                label,
+                value,
+                first_width = longest_account_length + 4,
+                second_width = longest_value_length
+            ),
+        });
 
     Ok(())
 }
 
-fn filter_accounts(accounts: Vec<Account>, opts: &AccountsOpts) -> Vec<Account> {
-    if opts.display_all() {
-        accounts
-    } else {
-        accounts
-            .into_iter()
-            .filter(|account| account.name() != "Equities:Starting Balances")
-            .collect()
+fn filter_accounts(balances: &mut Balances, opts: &AccountsOpts) {
+    if !opts.display_all() {
+        balances.retain(|account| account.name() != "Equities:Starting Balances");
     }
+
+    let filter = opts.account_filter().account_filter();
+    balances.retain(|account| filter.matches(account.name()));
+}
+
+/// Lists every account, parents included, indented by its depth.
+fn tree_rows(accounts: &[Account], commodities: &Commodities) -> Vec<(String, String)> {
+    accounts
+        .iter()
+        .flat_map(|account| {
+            let label = format!(
+                "{}{}",
+                "  ".repeat(account.depth() - 1),
+                account.leaf_name()
+            );
+            balance_rows(label, account, commodities)
+        })
+        .collect()
+}
+
+/// Lists every account with postings by its full name, folding accounts
+/// deeper than `depth` into their ancestors.
+fn flat_rows(
+    balances: &Balances,
+    depth: Option<usize>,
+    commodities: &Commodities,
+) -> Vec<(String, String)> {
+    let accounts = match depth {
+        Some(depth) => balances.collapse(depth).into_accounts(),
+        None => balances.clone().into_accounts(),
+    };
+
+    accounts
+        .iter()
+        .flat_map(|account| balance_rows(account.name().to_owned(), account, commodities))
+        .collect()
+}
+
+/// Lists an account's balance one commodity per row, with the label only on
+/// the first.
+fn balance_rows(
+    label: String,
+    account: &Account,
+    commodities: &Commodities,
+) -> Vec<(String, String)> {
+    commodities
+        .format_all(account.balance())
+        .into_iter()
+        .enumerate()
+        .map(|(idx, value)| match idx {
+            0 => (label.clone(), value),
+            _ => (String::new(), value),
+        })
+        .collect()
 }
@@ -1,4 +1,3 @@
 mod command;
-mod reconcile_accounts;
 
 pub use command::command;
@@ -0,0 +1,147 @@
+use std::{
+    env,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use sixpence::{
+    transaction::validate_and_normalize_entries, Commodities, Error, Lots, Transaction,
+};
+
+use ulid::Ulid;
+
+use crate::cli::{EditOpts, GlobalArgs};
+
+pub fn edit_transaction(global_args: &GlobalArgs, opts: &EditOpts) -> Result<(), Error> {
+    let mut ledger = global_args.open_ledger_for_writing()?;
+    let original = ledger.find_transaction(opts.id())?;
+    let commodities = ledger.commodities();
+
+    let edited = if opts.use_editor() {
+        edit_in_editor(original, &commodities)?
+    } else {
+        // Sales are matched against the lots as they'd stand without the
+        // transaction being edited.
+        let lots = ledger
+            .transactions()
+            .iter()
+            .filter(|transaction| transaction.id() != original.id())
+            .collect::<Lots>();
+        edit_with_opts(original, opts, &lots, &commodities)?
+    };
+
+    if global_args.strict() {
+        ledger.account_declarations().check_transaction(&edited)?;
+    }
+
+    let id = edited.id();
+    ledger.replace_transaction(edited)?;
+    ledger.save()?;
+
+    println!("Transaction {} updated.", id);
+
+    Ok(())
+}
+
+fn edit_with_opts(
+    original: &Transaction,
+    opts: &EditOpts,
+    lots: &Lots,
+    commodities: &Commodities,
+) -> Result<Transaction, Error> {
+    let entries = match opts.has_entries() {
+        false => original.entries().to_owned(),
+        true => {
+            let entries = opts.entries(commodities)?;
+            let entries = lots.book(entries.as_slice(), opts.lot_method())?;
+            validate_and_normalize_entries(entries.as_slice(), commodities)?
+        }
+    };
+
+    Ok(Transaction::new(
+        opts.date().unwrap_or_else(|| original.date()),
+        original.id(),
+        opts.status().unwrap_or_else(|| original.status()),
+        opts.description()
+            .to_owned()
+            .unwrap_or_else(|| original.description().to_owned()),
+        entries,
+    ))
+}
+
+/// Opens the transaction in the user's editor, and reads it back once the
+/// editor exits. The transaction keeps its id, whatever happens to it in the
+/// editor.
+fn edit_in_editor(original: &Transaction, commodities: &Commodities) -> Result<Transaction, Error> {
+    let (path, mut file) = create_temp_file()?;
+    let written = writeln!(file, "{}", original);
+    drop(file);
+    if let Err(err) = written {
+        let _ = fs::remove_file(path);
+        return Err(err.into());
+    }
+
+    let result = run_editor(path.as_path()).and_then(|_| {
+        let contents = fs::read_to_string(path.as_path())?;
+        let edited = Transaction::parse_with(contents.as_str(), commodities).map_err(|errors| {
+            let errors = errors
+                .into_iter()
+                .map(|err| err.in_file(path.clone()))
+                .collect::<Vec<Error>>();
+
+            match errors.len() {
+                1 => errors.into_iter().next().unwrap(),
+                _ => Error::multiple(errors),
+            }
+        })?;
+        edited.validate(commodities)?;
+
+        Ok(Transaction::new(
+            edited.date(),
+            original.id(),
+            edited.status(),
+            edited.description().to_owned(),
+            edited.entries().to_owned(),
+        ))
+    });
+
+    let _ = fs::remove_file(path);
+    result
+}
+
+/// Creates a new file in the temp directory that only the user can read or
+/// write. Its name is random, and it's never opened if something's already
+/// there, so no one else can read the transaction or have it written through
+/// a link of theirs.
+fn create_temp_file() -> Result<(PathBuf, File), Error> {
+    let path = env::temp_dir().join(format!("sixp-{}.txt", Ulid::new()));
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let file = options.open(path.as_path())?;
+    Ok((path, file))
+}
+
+fn run_editor(path: &Path) -> Result<(), Error> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_owned());
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or("vi");
+
+    let status = Command::new(program).args(words).arg(path).status()?;
+    match status.success() {
+        true => Ok(()),
+        false => Err(Error::io(io::Error::other(format!(
+            "{} exited with {}; the transaction was left unchanged",
+            program, status
+        )))),
+    }
+}
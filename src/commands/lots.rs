@@ -0,0 +1,53 @@
+use sixpence::Error;
+
+use crate::cli::{GlobalArgs, LotsOpts};
+
+pub fn lots(global_args: &GlobalArgs, opts: &LotsOpts) -> Result<(), Error> {
+    let ledger = global_args.open_ledger()?;
+    let commodities = ledger.commodities();
+    let filter = opts.account_filter().account_filter();
+
+    let lots = ledger.lots();
+    let rows = lots
+        .iter()
+        .filter(|lot| filter.matches(lot.account()))
+        .map(|lot| {
+            [
+                lot.account().to_owned(),
+                lot.date().format("%Y-%m-%d").to_string(),
+                commodities.format(lot.quantity()),
+                commodities.format(lot.cost()),
+                commodities.format(&lot.cost_basis(&commodities)),
+            ]
+        })
+        .collect::<Vec<[String; 5]>>();
+
+    if rows.is_empty() {
+        println!("No open lots found in the ledger.");
+        return Ok(());
+    }
+
+    let widths = rows.iter().fold([0; 5], |mut widths, row| {
+        for (width, column) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(column.chars().count());
+        }
+        widths
+    });
+
+    for [account, date, quantity, cost, basis] in rows.iter() {
+        println!(
+            "{:<account_width$}  {}  {:>quantity_width$}  @ {:>cost_width$}  {:>basis_width$}",
+            account,
+            date,
+            quantity,
+            cost,
+            basis,
+            account_width = widths[0],
+            quantity_width = widths[2],
+            cost_width = widths[3],
+            basis_width = widths[4]
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use std::fs;
+
+use sixpence::{
+    services::{backup_file_path, ledger_version, LEDGER_VERSION},
+    Error, LedgerFormat,
+};
+
+use crate::cli::GlobalArgs;
+
+pub fn migrate(global_args: &GlobalArgs) -> Result<(), Error> {
+    let ledger = global_args.open_ledger_for_writing()?;
+    let version = ledger_version(ledger.path())?;
+
+    if ledger.format() == LedgerFormat::Binary || version == LEDGER_VERSION {
+        println!(
+            "Ledger file is already at the current version ({}).",
+            LEDGER_VERSION
+        );
+        return Ok(());
+    }
+
+    // Saving keeps the previous version as a backup whatever `--backups`
+    // says, since older versions of sixp can't read the migrated ledger. An
+    // empty ledger file has nothing worth keeping, so it isn't backed up.
+    let is_empty = fs::metadata(ledger.path())?.len() == 0;
+    ledger.save()?;
+
+    if is_empty {
+        println!(
+            "Ledger file migrated from version {} to {}.",
+            version, LEDGER_VERSION
+        );
+    } else {
+        println!(
+            "Ledger file migrated from version {} to {}. The previous version was kept at: {}",
+            version,
+            LEDGER_VERSION,
+            backup_file_path(ledger.path(), 1).display()
+        );
+    }
+
+    Ok(())
+}
@@ -1,7 +1,32 @@
+mod account;
+mod check;
+mod confirm;
+mod convert;
 mod display_accounts;
+mod edit_transaction;
+mod lots;
+mod migrate;
 mod new_ledger;
 mod new_transaction;
+mod prices;
+mod reconcile;
+mod register;
+mod remove_transaction;
+mod show_transaction;
+mod void_transaction;
 
+pub use account::account;
+pub use check::check;
+pub use convert::convert;
 pub use display_accounts::command as display_accounts;
+pub use edit_transaction::edit_transaction;
+pub use lots::lots;
+pub use migrate::migrate;
 pub use new_ledger::new_ledger;
 pub use new_transaction::new_transaction;
+pub use prices::prices;
+pub use reconcile::reconcile;
+pub use register::register;
+pub use remove_transaction::remove_transaction;
+pub use show_transaction::show_transaction;
+pub use void_transaction::void_transaction;
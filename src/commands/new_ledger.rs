@@ -1,24 +1,24 @@
-use std::{
-    fs::{DirBuilder, OpenOptions},
-    path::Path,
-};
+use sixpence::{Error, Ledger, LedgerFormat};
 
-use crate::error::Error;
+use crate::cli::{GlobalArgs, NewOpts};
 
-pub fn new_ledger<'p>(ledger_file_path: &'p Path) -> Result<(), Error> {
-    let parent = ledger_file_path
-        .parent()
-        .ok_or(Error::invalid_ledger_file(ledger_file_path.to_owned()))?;
-    DirBuilder::new().recursive(true).create(parent)?;
+pub fn new_ledger(global_args: &GlobalArgs, opts: &NewOpts) -> Result<(), Error> {
+    let ledger_file_path = global_args.ledger_file()?;
+    let mut ledger = Ledger::create(ledger_file_path.as_path())?;
 
-    OpenOptions::new()
-        .create(true)
-        .write(true)
-        .open(ledger_file_path)?;
+    let format = opts
+        .format()
+        .unwrap_or_else(|| LedgerFormat::for_path(ledger_file_path.as_path()));
+    // Saving an empty ledger writes the header that marks which version of
+    // the format it's in. Ledgers that already hold anything are left alone.
+    if ledger.transactions().is_empty() && ledger.directives().is_empty() {
+        ledger.set_format(format);
+        ledger.save()?;
+    }
 
     println!(
         "New ledger file created at: {}",
-        ledger_file_path.to_str().unwrap()
+        ledger.path().to_str().unwrap()
     );
 
     Ok(())
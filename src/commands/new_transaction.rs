@@ -1,31 +1,35 @@
-use std::path::{Path, PathBuf};
+use sixpence::{services::insert_transaction, Commodities, Error, Lots};
 
-use crate::{
-    cli::TransactionOpts,
-    error::Error,
-    services::{read_transactions, write_transactions},
-    transaction::Transaction,
-};
+use crate::cli::{GlobalArgs, TransactionOpts};
 
-pub fn new_transaction<'p>(
-    ledger_file_path: &'p Path,
-    opts: &TransactionOpts,
-) -> Result<(), Error> {
-    if !ledger_file_path.exists() {
-        return Err(Error::ledger_file_not_found(PathBuf::from(
-            ledger_file_path,
-        )));
-    }
-
-    let mut transactions = read_transactions(ledger_file_path)?;
-    let new_transaction = Transaction::try_from(opts)?;
+pub fn new_transaction(global_args: &GlobalArgs, opts: &TransactionOpts) -> Result<(), Error> {
+    // Decimal values, costs and prices need the currency format, commodities
+    // and lots already in the ledger, and strict mode its declared accounts;
+    // otherwise it doesn't have to be read just to append to it.
+    if opts.needs_ledger() || global_args.strict() {
+        let mut ledger = global_args.open_ledger_for_writing()?;
+        let new_transaction = opts.transaction(&ledger.lots(), &ledger.commodities())?;
+        if global_args.strict() {
+            ledger
+                .account_declarations()
+                .check_transaction(&new_transaction)?;
+        }
 
-    transactions.push(new_transaction);
-    transactions.sort();
+        ledger.add_transaction(new_transaction)?;
+        ledger.save()?;
+    } else {
+        let ledger_file_path = global_args.ledger_file()?;
+        let _lock = global_args.lock_ledger()?;
+        let new_transaction = opts.transaction(&Lots::new(), &Commodities::new())?;
 
-    write_transactions(ledger_file_path, transactions.as_slice())?;
+        insert_transaction(
+            ledger_file_path.as_path(),
+            &new_transaction,
+            global_args.backups(),
+        )?;
+    }
 
-	println!("New transaction added to the ledger.");
+    println!("New transaction added to the ledger.");
 
     Ok(())
 }
@@ -0,0 +1,80 @@
+use sixpence::{Directive, Error, Price};
+
+use crate::cli::{AddPriceOpts, GlobalArgs, ListPricesOpts, PricesCommand, PricesOpts};
+
+pub fn prices(global_args: &GlobalArgs, opts: &PricesOpts) -> Result<(), Error> {
+    match opts.command() {
+        Some(PricesCommand::Add(opts)) => add_price(global_args, opts),
+        Some(PricesCommand::List(opts)) => list_prices(global_args, opts),
+        None => list_prices(global_args, &ListPricesOpts::default()),
+    }
+}
+
+fn list_prices(global_args: &GlobalArgs, opts: &ListPricesOpts) -> Result<(), Error> {
+    let ledger = global_args.open_ledger()?;
+    let commodities = ledger.commodities();
+    let date_range = opts.date_range().date_range();
+
+    let rows = ledger
+        .prices()
+        .iter()
+        .filter(|price| {
+            date_range.contains(price.date())
+                && opts
+                    .commodity()
+                    .as_ref()
+                    .is_none_or(|commodity| price.commodity() == commodity)
+        })
+        .map(|price| {
+            (
+                price.date().format("%Y-%m-%d").to_string(),
+                price.commodity().to_owned(),
+                commodities.format(price.price()),
+            )
+        })
+        .collect::<Vec<(String, String, String)>>();
+
+    if rows.is_empty() {
+        println!("No prices found in the ledger.");
+        return Ok(());
+    }
+
+    let (commodity_width, price_width) = rows
+        .iter()
+        .map(|(_, commodity, price)| (commodity.chars().count(), price.chars().count()))
+        .fold((0, 0), |(commodity_a, price_a), (commodity_b, price_b)| {
+            (commodity_a.max(commodity_b), price_a.max(price_b))
+        });
+
+    for (date, commodity, price) in rows.iter() {
+        println!(
+            "{}  {:<commodity_width$}  {:>price_width$}",
+            date,
+            commodity,
+            price,
+            commodity_width = commodity_width,
+            price_width = price_width
+        );
+    }
+
+    Ok(())
+}
+
+fn add_price(global_args: &GlobalArgs, opts: &AddPriceOpts) -> Result<(), Error> {
+    let mut ledger = global_args.open_ledger_for_writing()?;
+    let commodities = ledger.commodities();
+    let price = Price::new(opts.date(), opts.commodity(), opts.price(&commodities)?)?;
+    let formatted = commodities.format(price.price());
+
+    ledger.add_directive(Directive::Price(price));
+    ledger.save()?;
+
+    println!(
+        "Price of {} on {} recorded as {}.",
+        opts.commodity(),
+        opts.date().format("%Y-%m-%d"),
+        formatted
+    );
+
+    Ok(())
+}
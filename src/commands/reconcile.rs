@@ -0,0 +1,217 @@
+use std::{
+    collections::BTreeSet,
+    io::{self, BufRead, Write},
+};
+
+use chrono::{Date, Utc};
+use sixpence::{Amount, Directive, Error, Reconciliation, Status, Transaction};
+use ulid::Ulid;
+
+use crate::cli::{GlobalArgs, ReconcileOpts};
+
+/// An unreconciled transaction's postings to the account being reconciled.
+struct Candidate {
+    id: Ulid,
+    date: Date<Utc>,
+    description: String,
+    value: isize,
+    selected: bool,
+}
+
+pub fn reconcile(global_args: &GlobalArgs, opts: &ReconcileOpts) -> Result<(), Error> {
+    let mut ledger = global_args.open_ledger_for_writing()?;
+    let account = opts.account().as_str();
+    let commodities = ledger.commodities();
+    let statement_balance = &opts.statement_balance(&commodities)?;
+    let commodity = statement_balance.commodity().as_str();
+    let format = |value: isize| commodities.format(&Amount::new(value, commodity));
+    if ledger.transactions_for_account(account).next().is_none() {
+        println!("No postings to {} found in the ledger.", account);
+        return Ok(());
+    }
+
+    let last = ledger
+        .reconciliations(account)
+        .filter(|reconciliation| reconciliation.balance().commodity() == commodity)
+        .last();
+    if let Some(last) = last {
+        println!(
+            "Last reconciled on {} with a balance of {}.\n",
+            last.date().format("%Y-%m-%d"),
+            commodities.format(last.balance())
+        );
+    }
+
+    let reconciled_balance = ledger
+        .transactions_for_account(account)
+        .filter(|transaction| {
+            transaction.status() == Status::Reconciled
+                && transaction.date() <= opts.statement_date()
+        })
+        .map(|transaction| account_value(transaction, account, commodity))
+        .sum::<isize>();
+    let mut candidates = ledger
+        .transactions_for_account(account)
+        .filter(|transaction| {
+            transaction.status() != Status::Reconciled
+                && transaction.date() <= opts.statement_date()
+                && transaction
+                    .entries()
+                    .iter()
+                    .any(|entry| entry.account() == account && entry.commodity() == commodity)
+        })
+        .map(|transaction| Candidate {
+            id: transaction.id(),
+            date: transaction.date(),
+            description: transaction.description().to_owned(),
+            value: account_value(transaction, account, commodity),
+            selected: transaction.status() == Status::Pending,
+        })
+        .collect::<Vec<Candidate>>();
+
+    let mut lines = io::stdin().lock().lines();
+    loop {
+        let difference = statement_balance.value()
+            - reconciled_balance
+            - candidates
+                .iter()
+                .filter(|candidate| candidate.selected)
+                .map(|candidate| candidate.value)
+                .sum::<isize>();
+        print_candidates(candidates.as_slice(), format);
+        println!();
+        println!(
+            "Statement balance  {}",
+            commodities.format(statement_balance)
+        );
+        println!("Difference         {}", format(difference));
+        println!();
+
+        print!("Toggle postings by number (e.g. 1 3 5-7), or (a)ll, (n)one, (f)inish, (q)uit: ");
+        io::stdout().flush()?;
+
+        let line = match lines.next() {
+            Some(line) => line?,
+            None => "q".to_owned(),
+        };
+        println!();
+
+        match line.trim() {
+            "" => {}
+            "a" | "all" => candidates.iter_mut().for_each(|c| c.selected = true),
+            "n" | "none" => candidates.iter_mut().for_each(|c| c.selected = false),
+            "q" | "quit" => {
+                println!("Reconciliation abandoned; the ledger was left unchanged.");
+                return Ok(());
+            }
+            "f" | "finish" if difference != 0 => {
+                println!(
+                    "The postings are {} away from the statement balance; keep toggling, or \
+                     quit and fix the ledger first.\n",
+                    format(difference)
+                );
+            }
+            "f" | "finish" => break,
+            selection => match parse_selection(selection, candidates.len()) {
+                Ok(numbers) => numbers
+                    .into_iter()
+                    .for_each(|number| candidates[number - 1].selected ^= true),
+                Err(message) => println!("{}\n", message),
+            },
+        }
+    }
+
+    let selected = candidates
+        .iter()
+        .filter(|candidate| candidate.selected)
+        .collect::<Vec<&Candidate>>();
+    for candidate in selected.iter() {
+        ledger.set_status(candidate.id, Status::Reconciled)?;
+    }
+    ledger.add_directive(Directive::Reconciliation(Reconciliation::new(
+        opts.statement_date(),
+        account,
+        statement_balance.clone(),
+    )));
+    ledger.save()?;
+
+    println!(
+        "Reconciled {} transactions; {} balances at {} as of {}.",
+        selected.len(),
+        account,
+        commodities.format(statement_balance),
+        opts.statement_date().format("%Y-%m-%d")
+    );
+
+    Ok(())
+}
+
+fn account_value(transaction: &Transaction, account: &str, commodity: &str) -> isize {
+    transaction
+        .entries()
+        .iter()
+        .filter(|entry| entry.account() == account && entry.commodity() == commodity)
+        .map(|entry| entry.value())
+        .sum()
+}
+
+fn print_candidates<F: Fn(isize) -> String>(candidates: &[Candidate], format: F) {
+    if candidates.is_empty() {
+        println!("No unreconciled postings up to the statement date.");
+        return;
+    }
+
+    let values = candidates
+        .iter()
+        .map(|candidate| format(candidate.value))
+        .collect::<Vec<String>>();
+    let number_width = candidates.len().to_string().len();
+    let description_width = candidates
+        .iter()
+        .map(|candidate| candidate.description.chars().count())
+        .max()
+        .unwrap_or_default();
+    let value_width = values
+        .iter()
+        .map(|value| value.chars().count())
+        .max()
+        .unwrap_or_default();
+
+    for (idx, (candidate, value)) in candidates.iter().zip(values.iter()).enumerate() {
+        println!(
+            "{:>number_width$} [{}] {} {:<description_width$}  {:>value_width$}",
+            idx + 1,
+            if candidate.selected { "x" } else { " " },
+            candidate.date.format("%Y-%m-%d"),
+            candidate.description,
+            value,
+            number_width = number_width,
+            description_width = description_width,
+            value_width = value_width
+        );
+    }
+}
+
+/// Parses a list of 1-indexed posting numbers and ranges like `1 3 5-7`.
+fn parse_selection(selection: &str, count: usize) -> Result<BTreeSet<usize>, String> {
+    let mut numbers = BTreeSet::<usize>::new();
+    for part in selection.split(|c: char| c == ',' || c.is_whitespace()) {
+        if part.is_empty() {
+            continue;
+        }
+
+        let (start, end) = part.split_once('-').unwrap_or((part, part));
+        let range = match (start.trim().parse::<usize>(), end.trim().parse::<usize>()) {
+            (Ok(start), Ok(end)) if start >= 1 && start <= end && end <= count => start..=end,
+            _ => {
+                return Err(format!(
+                    "'{}' isn't a posting number between 1 and {}.",
+                    part, count
+                ))
+            }
+        };
+        numbers.extend(range);
+    }
+
+    Ok(numbers)
+}
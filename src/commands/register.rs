@@ -0,0 +1,73 @@
+use sixpence::{Amount, Error};
+
+use crate::cli::{GlobalArgs, RegisterOpts};
+
+pub fn register(global_args: &GlobalArgs, opts: &RegisterOpts) -> Result<(), Error> {
+    let ledger = global_args.open_ledger()?;
+    let commodities = ledger.commodities();
+    let filter = opts.account_filter().account_filter();
+    let mut postings = ledger.register(&filter, opts.date_range().date_range());
+
+    if let Some(limit) = opts.limit() {
+        postings.drain(..postings.len().saturating_sub(limit));
+    }
+
+    if opts.reverse() {
+        postings.reverse();
+    }
+
+    if postings.is_empty() {
+        println!("No postings found in the ledger.");
+        return Ok(());
+    }
+
+    let rows = postings
+        .iter()
+        .map(|posting| {
+            let transaction = posting.transaction();
+            [
+                transaction.date().format("%Y-%m-%d").to_string(),
+                transaction.id().to_string(),
+                transaction
+                    .status()
+                    .marker()
+                    .map_or_else(String::new, String::from),
+                transaction.description().to_owned(),
+                posting.entry().account().to_owned(),
+                commodities.format(posting.entry().amount()),
+                commodities.format(&Amount::new(
+                    posting.running_balance(),
+                    posting.entry().commodity(),
+                )),
+            ]
+        })
+        .collect::<Vec<[String; 7]>>();
+
+    let widths = rows.iter().fold([0; 7], |mut widths, row| {
+        for (width, column) in widths.iter_mut().zip(row) {
+            *width = (*width).max(column.chars().count());
+        }
+
+        widths
+    });
+
+    for [date, id, status, description, account, amount, balance] in rows.iter() {
+        println!(
+            "{} {} {:<status_width$} {:description_width$}  {:account_width$}  {:>amount_width$}  {:>balance_width$}",
+            date,
+            id,
+            status,
+            description,
+            account,
+            amount,
+            balance,
+            status_width = widths[2],
+            description_width = widths[3],
+            account_width = widths[4],
+            amount_width = widths[5],
+            balance_width = widths[6],
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use sixpence::Error;
+
+use super::confirm::confirm;
+use crate::cli::{GlobalArgs, RemoveOpts};
+
+pub fn remove_transaction(global_args: &GlobalArgs, opts: &RemoveOpts) -> Result<(), Error> {
+    let mut ledger = global_args.open_ledger_for_writing()?;
+    let transaction = ledger.find_transaction(opts.id())?;
+    let id = transaction.id();
+
+    if !opts.assume_yes() {
+        println!("{}\n", transaction);
+        if !confirm("Delete this transaction from the ledger?")? {
+            println!("Transaction {} was left in the ledger.", id);
+            return Ok(());
+        }
+    }
+
+    ledger.remove_transaction(id)?;
+    ledger.save()?;
+
+    println!("Transaction {} deleted.", id);
+
+    Ok(())
+}
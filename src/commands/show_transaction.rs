@@ -0,0 +1,76 @@
+use sixpence::{Commodities, Entry, Error};
+
+use crate::cli::{GlobalArgs, ShowOpts};
+
+pub fn show_transaction(global_args: &GlobalArgs, opts: &ShowOpts) -> Result<(), Error> {
+    let ledger = global_args.open_ledger()?;
+    let transaction = ledger.find_transaction(opts.id())?;
+    let commodities = ledger.commodities();
+
+    println!("Transaction  {}", transaction.id());
+    println!("Date         {}", transaction.date().format("%Y-%m-%d"));
+    println!("Status       {}", transaction.status());
+    println!("Description  {}", transaction.description());
+    println!();
+
+    let entries = transaction
+        .entries()
+        .iter()
+        .map(|entry| (entry.account(), format_entry(entry, &commodities)))
+        .collect::<Vec<(&String, String)>>();
+    let (longest_account_length, longest_value_length) = entries
+        .iter()
+        .map(|(account, value)| (account.chars().count(), value.chars().count()))
+        .fold((0, 0), |(account_a, amount_a), (account_b, amount_b)| {
+            (account_a.max(account_b), amount_a.max(amount_b))
+        });
+
+    for (account, value) in entries.iter() {
+        println!(
+            "    {:.<first_width$}{:>second_width$}",
+            account,
+            value,
+            first_width = longest_account_length + 4,
+            second_width = longest_value_length
+        );
+    }
+
+    println!();
+    let balance = transaction.balance(&commodities);
+    match balance.is_zero() {
+        true => println!("Balanced"),
+        false => println!(
+            "Unbalanced by {}",
+            commodities.format_all(&balance).join(", ")
+        ),
+    }
+
+    Ok(())
+}
+
+/// Formats an entry's amount, followed by the cost or price of each unit and
+/// the date of the lot a sale draws down.
+fn format_entry(entry: &Entry, commodities: &Commodities) -> String {
+    let amount = commodities.format(entry.amount());
+    match (entry.cost(), entry.price()) {
+        (Some(cost), Some(price)) => match entry.lot_date() {
+            Some(lot_date) => format!(
+                "{} {{{}, {}}} @ {}",
+                amount,
+                commodities.format(cost),
+                lot_date.format("%Y-%m-%d"),
+                commodities.format(price)
+            ),
+            None => format!(
+                "{} {{{}}} @ {}",
+                amount,
+                commodities.format(cost),
+                commodities.format(price)
+            ),
+        },
+        (Some(per_unit), None) | (None, Some(per_unit)) => {
+            format!("{} @ {}", amount, commodities.format(per_unit))
+        }
+        (None, None) => amount,
+    }
+}
@@ -0,0 +1,31 @@
+use chrono::Utc;
+use sixpence::Error;
+
+use super::confirm::confirm;
+use crate::cli::{GlobalArgs, VoidOpts};
+
+pub fn void_transaction(global_args: &GlobalArgs, opts: &VoidOpts) -> Result<(), Error> {
+    let mut ledger = global_args.open_ledger_for_writing()?;
+    let transaction = ledger.find_transaction(opts.id())?;
+    let reversal = transaction.reversal(opts.date().unwrap_or_else(|| Utc::now().date()));
+    if global_args.strict() {
+        ledger.account_declarations().check_transaction(&reversal)?;
+    }
+
+    if !opts.assume_yes() {
+        println!("{}\n", transaction);
+        if !confirm("Void this transaction with a reversing one?")? {
+            println!("Transaction {} was left as it is.", transaction.id());
+            return Ok(());
+        }
+    }
+
+    let id = transaction.id();
+    let reversal_id = reversal.id();
+    ledger.add_transaction(reversal)?;
+    ledger.save()?;
+
+    println!("Transaction {} voided by {}.", id, reversal_id);
+
+    Ok(())
+}
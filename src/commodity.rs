@@ -0,0 +1,200 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use chrono::{Date, Utc};
+use getset::{CopyGetters, Getters, Setters};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    amount::{is_commodity_symbol, Amount, Amounts},
+    currency::CurrencyFormat,
+    directive::Directive,
+    error::Error,
+    transaction::{parse_date, split_with_columns},
+};
+
+/// The commodity of amounts written without one: the ledger's home currency.
+pub const DEFAULT_COMMODITY: &str = "";
+
+/// The number of decimal places of a commodity that was never declared.
+pub const DEFAULT_PRECISION: u32 = 2;
+
+/// The number of decimal places amounts of the home currency are stored in,
+/// however many a `currency` directive shows them with.
+pub const HOME_CURRENCY_PRECISION: u32 = 2;
+
+/// A declaration of how many decimal places a commodity's amounts have,
+/// written to the ledger as `YYYY-MM-DD commodity <symbol> <precision>`.
+///
+/// Amounts are always stored in the commodity's smallest unit, so with a
+/// precision of 4, `10000 AAPL` in the ledger is one share.
+#[derive(Clone, CopyGetters, Debug, Deserialize, Eq, Getters, PartialEq, Serialize)]
+pub struct Commodity {
+    #[getset(get_copy = "pub")]
+    #[serde(with = "crate::serde_date")]
+    date: Date<Utc>,
+
+    #[getset(get = "pub")]
+    symbol: String,
+
+    #[getset(get_copy = "pub")]
+    precision: u32,
+}
+
+impl Commodity {
+    pub const KEYWORD: &'static str = "commodity";
+
+    pub fn new(date: Date<Utc>, symbol: &str, precision: u32) -> Self {
+        Self {
+            date,
+            symbol: symbol.to_owned(),
+            precision,
+        }
+    }
+}
+
+impl TryFrom<&str> for Commodity {
+    type Error = Error;
+
+    fn try_from(line: &str) -> Result<Self, Self::Error> {
+        let parts = split_with_columns(line);
+        let end = line.trim_end().len();
+        let (date, symbol, precision) = match parts.as_slice() {
+            [date, _, symbol, precision] => (date, symbol, precision),
+            [_, _, _, _, extra, ..] => {
+                return Err(
+                    Error::invalid_directive("expected a symbol and a precision")
+                        .at_columns(extra.0.start..end),
+                )
+            }
+            _ => {
+                return Err(
+                    Error::invalid_directive("expected a symbol and a precision")
+                        .at_columns(end..end + 1),
+                )
+            }
+        };
+
+        let (date_columns, date) = date;
+        let date = parse_date(date, date_columns.clone())?;
+
+        let (symbol_columns, symbol) = symbol;
+        if !is_commodity_symbol(symbol) || *symbol == Self::KEYWORD {
+            return Err(Error::invalid_directive("invalid commodity symbol")
+                .at_columns(symbol_columns.clone()));
+        }
+
+        let (precision_columns, precision) = precision;
+        let precision = precision
+            .parse::<u32>()
+            .ok()
+            .filter(|precision| *precision <= 18)
+            .ok_or_else(|| {
+                Error::invalid_directive("precision must be a whole number from 0 to 18")
+                    .at_columns(precision_columns.clone())
+            })?;
+
+        Ok(Self::new(date, symbol, precision))
+    }
+}
+
+impl Display for Commodity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.date.format("%Y-%m-%d"),
+            Self::KEYWORD,
+            self.symbol,
+            self.precision
+        )
+    }
+}
+
+/// The precision of every commodity declared in a ledger, and the format of
+/// its home currency, used to format their amounts.
+#[derive(Clone, Debug, Default, Getters, Setters)]
+pub struct Commodities {
+    precisions: BTreeMap<String, u32>,
+
+    #[getset(get = "pub", set = "pub")]
+    currency_format: CurrencyFormat,
+}
+
+impl Commodities {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The commodities declared by `directives`, with the home currency in
+    /// the format of the last `currency` directive among them.
+    pub fn from_directives<'a, I: IntoIterator<Item = &'a Directive>>(directives: I) -> Self {
+        let mut commodities = Self::new();
+        for directive in directives {
+            match directive {
+                Directive::Commodity(commodity) => commodities.declare(commodity),
+                Directive::Currency(currency) => {
+                    commodities.set_currency_format(currency.format().clone());
+                }
+                _ => {}
+            }
+        }
+
+        commodities
+    }
+
+    /// Records a commodity's precision. Later declarations of the same
+    /// commodity replace earlier ones.
+    pub fn declare(&mut self, commodity: &Commodity) {
+        self.precisions
+            .insert(commodity.symbol().to_owned(), commodity.precision());
+    }
+
+    pub fn precision(&self, commodity: &str) -> u32 {
+        match commodity {
+            DEFAULT_COMMODITY => HOME_CURRENCY_PRECISION,
+            commodity => self
+                .precisions
+                .get(commodity)
+                .copied()
+                .unwrap_or(DEFAULT_PRECISION),
+        }
+    }
+
+    /// Formats an amount for display, e.g. `(1,234.56)` in the home
+    /// currency, or `10.5000 AAPL`.
+    pub fn format(&self, amount: &Amount) -> String {
+        match amount.commodity().as_str() {
+            DEFAULT_COMMODITY => self.currency_format.format(amount.value()),
+            commodity => self.currency_format.format_quantity(
+                amount.value(),
+                self.precision(commodity),
+                commodity,
+            ),
+        }
+    }
+
+    /// Formats every commodity in `amounts`, one per item, with a zero total
+    /// formatted as a single item.
+    pub fn format_all(&self, amounts: &Amounts) -> Vec<String> {
+        let formatted = amounts
+            .iter()
+            .map(|amount| self.format(&amount))
+            .collect::<Vec<String>>();
+
+        match formatted.is_empty() {
+            true => vec![self.currency_format.format(0)],
+            false => formatted,
+        }
+    }
+}
+
+impl<'a> FromIterator<&'a Commodity> for Commodities {
+    fn from_iter<I: IntoIterator<Item = &'a Commodity>>(commodities: I) -> Self {
+        let mut declared = Self::new();
+        for commodity in commodities {
+            declared.declare(commodity);
+        }
+
+        declared
+    }
+}
@@ -0,0 +1,407 @@
+use std::fmt::Display;
+
+use chrono::{Date, Utc};
+use getset::{CopyGetters, Getters, Setters};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    amount::divide_rounded,
+    commodity::HOME_CURRENCY_PRECISION,
+    error::Error,
+    transaction::{parse_date, split_with_columns},
+};
+
+/// Which side of the value the currency symbol goes on.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum SymbolPlacement {
+    Before,
+    After,
+}
+
+/// How digits left of the decimal separator are grouped.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Grouping {
+    /// Groups of three, e.g. `1,234,567`.
+    Thousands,
+
+    /// A group of three followed by groups of two, e.g. `12,34,567`.
+    Indian,
+}
+
+/// How negative amounts are told apart from positive ones.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum NegativeStyle {
+    /// `(1,234.56)`
+    Parentheses,
+
+    /// `-1,234.56`
+    Minus,
+}
+
+/// How amounts of the ledger's home currency, and the numbers of other
+/// commodities, are formatted for display.
+///
+/// Values are formatted from their integer count of the smallest unit, so
+/// they're exact however large they get.
+#[derive(Clone, CopyGetters, Debug, Deserialize, Eq, Getters, PartialEq, Serialize, Setters)]
+pub struct CurrencyFormat {
+    #[getset(get = "pub", set = "pub")]
+    symbol: String,
+
+    #[getset(get_copy = "pub", set = "pub")]
+    placement: SymbolPlacement,
+
+    /// Whether a space separates the symbol from the value.
+    #[getset(get_copy = "pub", set = "pub")]
+    symbol_space: bool,
+
+    #[getset(get = "pub", set = "pub")]
+    thousands_separator: String,
+
+    #[getset(get = "pub", set = "pub")]
+    decimal_separator: String,
+
+    /// The number of decimal places the home currency is shown with. Its
+    /// values are stored in hundredths regardless, and rounded to this many
+    /// places when shown.
+    #[getset(get_copy = "pub", set = "pub")]
+    decimals: u32,
+
+    #[getset(get_copy = "pub", set = "pub")]
+    grouping: Grouping,
+
+    #[getset(get_copy = "pub", set = "pub")]
+    negative_style: NegativeStyle,
+
+    /// What a zero amount of the home currency is shown as, if not as a
+    /// number.
+    #[getset(get = "pub", set = "pub")]
+    zero: Option<String>,
+}
+
+impl CurrencyFormat {
+    /// The names of the locale presets, for [`CurrencyFormat::preset`].
+    pub const PRESETS: [&'static str; 4] = ["default", "en-US", "de-DE", "en-IN"];
+
+    /// The format conventionally used for a locale's currency, like `en-US`
+    /// for `$1,234.56`, `de-DE` for `1.234,56 €` or `en-IN` for
+    /// `₹12,34,567.89`. `default` is the format of a ledger that doesn't
+    /// configure one.
+    pub fn preset(locale: &str) -> Option<Self> {
+        let default = Self::default();
+        match locale {
+            "default" => Some(default),
+            "en-US" => Some(Self {
+                symbol: "$".to_owned(),
+                ..default
+            }),
+            "de-DE" => Some(Self {
+                symbol: "€".to_owned(),
+                placement: SymbolPlacement::After,
+                symbol_space: true,
+                thousands_separator: ".".to_owned(),
+                decimal_separator: ",".to_owned(),
+                negative_style: NegativeStyle::Minus,
+                zero: None,
+                ..default
+            }),
+            "en-IN" => Some(Self {
+                symbol: "₹".to_owned(),
+                grouping: Grouping::Indian,
+                negative_style: NegativeStyle::Minus,
+                zero: None,
+                ..default
+            }),
+            _ => None,
+        }
+    }
+
+    /// Formats a value of the home currency, counted in hundredths, e.g.
+    /// `-123456` as `(1,234.56)`. Values are rounded to `decimals` places,
+    /// halves away from zero.
+    pub fn format(&self, value: isize) -> String {
+        let (value, precision) = match self.decimals < HOME_CURRENCY_PRECISION {
+            true => {
+                let scale = 10i128.pow(HOME_CURRENCY_PRECISION - self.decimals);
+                (divide_rounded(value as i128, scale) as isize, self.decimals)
+            }
+            false => (value, HOME_CURRENCY_PRECISION),
+        };
+        if let (0, Some(zero)) = (value, self.zero.as_ref()) {
+            return zero.to_owned();
+        }
+
+        let mut number = self.format_number(value.unsigned_abs(), precision);
+        for _ in precision..self.decimals {
+            number.push('0');
+        }
+        let space = if self.symbol_space && !self.symbol.is_empty() {
+            " "
+        } else {
+            ""
+        };
+        let formatted = match self.placement {
+            SymbolPlacement::Before => format!("{}{}{}", self.symbol, space, number),
+            SymbolPlacement::After => format!("{}{}{}", number, space, self.symbol),
+        };
+
+        self.sign(value, formatted)
+    }
+
+    /// Formats a quantity of a commodity counted in units of
+    /// `10^-precision`, e.g. `-123456` with a precision of 3 as
+    /// `(123.456 BTC)`.
+    pub fn format_quantity(&self, value: isize, precision: u32, symbol: &str) -> String {
+        let number = self.format_number(value.unsigned_abs(), precision);
+
+        self.sign(value, format!("{} {}", number, symbol))
+    }
+
+    fn format_number(&self, magnitude: usize, precision: u32) -> String {
+        let scale = 10usize.pow(precision);
+        let whole = (magnitude / scale).to_string();
+
+        let mut grouped = String::with_capacity(whole.len() + whole.len() / 2);
+        for (idx, digit) in whole.chars().enumerate() {
+            let remaining = whole.len() - idx;
+            let starts_group = match self.grouping {
+                Grouping::Thousands => remaining.is_multiple_of(3),
+                Grouping::Indian => remaining == 3 || (remaining > 3 && remaining % 2 == 1),
+            };
+            if idx > 0 && starts_group {
+                grouped.push_str(self.thousands_separator.as_str());
+            }
+            grouped.push(digit);
+        }
+
+        match precision {
+            0 => grouped,
+            _ => format!(
+                "{}{}{:0width$}",
+                grouped,
+                self.decimal_separator,
+                magnitude % scale,
+                width = precision as usize
+            ),
+        }
+    }
+
+    fn sign(&self, value: isize, formatted: String) -> String {
+        match (value < 0, self.negative_style) {
+            (false, _) => formatted,
+            (true, NegativeStyle::Parentheses) => format!("({})", formatted),
+            (true, NegativeStyle::Minus) => format!("-{}", formatted),
+        }
+    }
+
+    /// Changes one setting by name, as written in a `currency` directive.
+    fn set_option(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let separator = |value: &str| match value {
+            "none" => String::new(),
+            "space" => " ".to_owned(),
+            value => value.to_owned(),
+        };
+
+        match (key, value) {
+            ("symbol", "none") => self.symbol = String::new(),
+            ("symbol", symbol) => self.symbol = symbol.to_owned(),
+            ("placement", "before") => self.placement = SymbolPlacement::Before,
+            ("placement", "after") => self.placement = SymbolPlacement::After,
+            ("symbol-space", "yes") => self.symbol_space = true,
+            ("symbol-space", "no") => self.symbol_space = false,
+            ("thousands", value) => self.thousands_separator = separator(value),
+            ("decimal", value) if !value.is_empty() && value != "none" => {
+                self.decimal_separator = separator(value)
+            }
+            ("decimals", value) => {
+                self.decimals = value
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|decimals| *decimals <= 18)
+                    .ok_or("decimals must be a whole number from 0 to 18")?
+            }
+            ("grouping", "thousands") => self.grouping = Grouping::Thousands,
+            ("grouping", "indian") => self.grouping = Grouping::Indian,
+            ("negative", "parentheses") => self.negative_style = NegativeStyle::Parentheses,
+            ("negative", "minus") => self.negative_style = NegativeStyle::Minus,
+            ("zero", "none") => self.zero = None,
+            ("zero", zero) => self.zero = Some(zero.to_owned()),
+            ("placement" | "symbol-space" | "decimal" | "grouping" | "negative", value) => {
+                return Err(format!("'{}' isn't a valid {}", value, key))
+            }
+            (key, _) => return Err(format!("unknown currency option '{}'", key)),
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for CurrencyFormat {
+    fn default() -> Self {
+        Self {
+            symbol: String::new(),
+            placement: SymbolPlacement::Before,
+            symbol_space: false,
+            thousands_separator: ",".to_owned(),
+            decimal_separator: ".".to_owned(),
+            decimals: HOME_CURRENCY_PRECISION,
+            grouping: Grouping::Thousands,
+            negative_style: NegativeStyle::Parentheses,
+            zero: Some("—".to_owned()),
+        }
+    }
+}
+
+/// A declaration of how the ledger's home currency is formatted, written to
+/// the ledger as `YYYY-MM-DD currency [<locale>] [<option>=<value> ...]`,
+/// e.g. `2026-01-01 currency de-DE decimals=0`.
+///
+/// The options are `symbol`, `placement` (`before` or `after`),
+/// `symbol-space` (`yes` or `no`), `thousands` and `decimal` separators,
+/// `decimals`, `grouping` (`thousands` or `indian`), `negative`
+/// (`parentheses` or `minus`) and `zero`. Separators may be given as `space`
+/// or `none`.
+#[derive(Clone, CopyGetters, Debug, Deserialize, Eq, Getters, PartialEq, Serialize)]
+pub struct Currency {
+    #[getset(get_copy = "pub")]
+    #[serde(with = "crate::serde_date")]
+    date: Date<Utc>,
+
+    /// The preset the options apply to.
+    #[getset(get = "pub")]
+    locale: Option<String>,
+
+    /// The options, in the order they were written.
+    #[getset(get = "pub")]
+    options: Vec<(String, String)>,
+
+    #[getset(get = "pub")]
+    format: CurrencyFormat,
+}
+
+impl Currency {
+    pub const KEYWORD: &'static str = "currency";
+}
+
+impl TryFrom<&str> for Currency {
+    type Error = Error;
+
+    fn try_from(line: &str) -> Result<Self, Self::Error> {
+        let parts = split_with_columns(line);
+        let end = line.trim_end().len();
+        if parts.len() < 3 {
+            return Err(
+                Error::invalid_directive("expected a locale or currency options")
+                    .at_columns(end..end + 1),
+            );
+        }
+
+        let (date_columns, date) = &parts[0];
+        let date = parse_date(date, date_columns.clone())?;
+
+        let mut options = &parts[2..];
+        let (locale, mut format) = match options.first() {
+            Some((columns, locale)) if !locale.contains('=') => {
+                options = &options[1..];
+                let format = CurrencyFormat::preset(locale).ok_or_else(|| {
+                    Error::invalid_directive(
+                        format!(
+                            "unknown locale '{}', expected one of {}",
+                            locale,
+                            CurrencyFormat::PRESETS.join(", ")
+                        )
+                        .as_str(),
+                    )
+                    .at_columns(columns.clone())
+                })?;
+
+                (Some(locale.to_string()), format)
+            }
+            _ => (None, CurrencyFormat::default()),
+        };
+
+        let options = options
+            .iter()
+            .map(|(columns, option)| {
+                let (key, value) = option.split_once('=').ok_or_else(|| {
+                    Error::invalid_directive("expected options in KEY=VALUE format")
+                        .at_columns(columns.clone())
+                })?;
+                format.set_option(key, value).map_err(|reason| {
+                    Error::invalid_directive(reason.as_str()).at_columns(columns.clone())
+                })?;
+
+                Ok((key.to_owned(), value.to_owned()))
+            })
+            .collect::<Result<Vec<(String, String)>, Error>>()?;
+
+        Ok(Self {
+            date,
+            locale,
+            options,
+            format,
+        })
+    }
+}
+
+impl Display for Currency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.date.format("%Y-%m-%d"), Self::KEYWORD)?;
+        if let Some(locale) = self.locale.as_ref() {
+            write!(f, " {}", locale)?;
+        }
+        for (key, value) in self.options.iter() {
+            write!(f, " {}={}", key, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Formats an amount in cents as currency, e.g. `-123456` as `(1,234.56)`.
+pub fn format_currency(cents: isize) -> String {
+    CurrencyFormat::default().format(cents)
+}
+
+/// Formats a quantity of a commodity counted in units of `10^-precision`,
+/// e.g. `-123456` with a precision of 3 as `(123.456 BTC)`.
+pub fn format_quantity(value: isize, precision: u32, symbol: &str) -> String {
+    CurrencyFormat::default().format_quantity(value, precision, symbol)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_decimals(decimals: u32) -> CurrencyFormat {
+        let mut format = CurrencyFormat::preset("en-US").unwrap();
+        format.set_decimals(decimals);
+        format
+    }
+
+    #[test]
+    fn rounds_to_fewer_decimals_for_display() {
+        let format = with_decimals(0);
+        assert_eq!(format.format(123456), "$1,235");
+        assert_eq!(format.format(-123449), "($1,234)");
+        assert_eq!(format.format(49), "—");
+    }
+
+    #[test]
+    fn pads_to_more_decimals_for_display() {
+        assert_eq!(with_decimals(4).format(-123456), "($1,234.5600)");
+    }
+
+    #[test]
+    fn leaves_stored_precision_alone() {
+        let currency = Currency::try_from("2026-01-01 currency en-US decimals=0").unwrap();
+        let mut commodities = crate::commodity::Commodities::new();
+        commodities.set_currency_format(currency.format().clone());
+
+        assert_eq!(commodities.precision(""), HOME_CURRENCY_PRECISION);
+        assert_eq!(
+            commodities.format(&crate::amount::Amount::of_default(1250)),
+            "$13"
+        );
+    }
+}
@@ -0,0 +1,241 @@
+use std::str::FromStr;
+
+use chrono::{Date, Datelike, Duration, NaiveDate, TimeZone, Utc};
+use getset::CopyGetters;
+
+use crate::error::Error;
+
+/// A span of days, inclusive of both ends. A missing end leaves that side of
+/// the range open.
+#[derive(Clone, Copy, CopyGetters, Debug, Default, Eq, PartialEq)]
+pub struct DateRange {
+    #[getset(get_copy = "pub")]
+    begin: Option<Date<Utc>>,
+
+    #[getset(get_copy = "pub")]
+    end: Option<Date<Utc>>,
+}
+
+impl DateRange {
+    pub fn new(begin: Option<Date<Utc>>, end: Option<Date<Utc>>) -> Self {
+        Self { begin, end }
+    }
+
+    pub fn contains(&self, date: Date<Utc>) -> bool {
+        self.begin.is_none_or(|begin| date >= begin) && self.end.is_none_or(|end| date <= end)
+    }
+
+    /// The days covered by both ranges.
+    pub fn intersect(&self, other: &DateRange) -> Self {
+        Self {
+            begin: self.begin.max(other.begin),
+            end: match (self.end, other.end) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            },
+        }
+    }
+
+    /// Parses a period relative to `today`.
+    ///
+    /// Periods can be a calendar year (`2026`), quarter (`2026-Q3`), month
+    /// (`2026-09`) or day (`2026-09-14`); `today` or `yesterday`; `this`,
+    /// `last` or `next` followed by `week`, `month`, `quarter` or `year`; or
+    /// `ytd`, `qtd` and `mtd` for the year, quarter or month to date.
+    pub fn period(period: &str, today: Date<Utc>) -> Result<Self, Error> {
+        let normalized = period
+            .split_whitespace()
+            .collect::<Vec<&str>>()
+            .join(" ")
+            .to_lowercase();
+        let today = today.naive_utc();
+
+        let (begin, end) = match normalized.as_str() {
+            "today" => (today, today),
+            "yesterday" => (today.pred(), today.pred()),
+            "ytd" => (year_of(today).0, today),
+            "qtd" => (quarter_of(today).0, today),
+            "mtd" => (month_of(today).0, today),
+            relative if relative.contains(' ') => {
+                let (which, unit) = relative.split_once(' ').unwrap();
+                let offset: i32 = match which {
+                    "last" => -1,
+                    "this" => 0,
+                    "next" => 1,
+                    _ => return Err(Error::invalid_period(period)),
+                };
+
+                match unit {
+                    "week" => {
+                        let monday = today
+                            - Duration::days(today.weekday().num_days_from_monday() as i64)
+                            + Duration::weeks(offset as i64);
+                        (monday, monday + Duration::days(6))
+                    }
+                    "month" => month_of(shift_months(today, offset)),
+                    "quarter" => quarter_of(shift_months(today, offset * 3)),
+                    "year" => year_of(shift_months(today, offset * 12)),
+                    _ => return Err(Error::invalid_period(period)),
+                }
+            }
+            absolute => {
+                parse_absolute_period(absolute).ok_or_else(|| Error::invalid_period(period))?
+            }
+        };
+
+        Ok(Self::new(
+            Some(Utc.from_utc_date(&begin)),
+            Some(Utc.from_utc_date(&end)),
+        ))
+    }
+}
+
+impl FromStr for DateRange {
+    type Err = Error;
+
+    fn from_str(period: &str) -> Result<Self, Self::Err> {
+        Self::period(period, Utc::now().date())
+    }
+}
+
+fn parse_absolute_period(period: &str) -> Option<(NaiveDate, NaiveDate)> {
+    let parts = period.split('-').collect::<Vec<&str>>();
+    let year = parts.first()?.parse::<i32>().ok()?;
+
+    match parts.as_slice() {
+        [_] => Some(year_of(NaiveDate::from_ymd_opt(year, 1, 1)?)),
+        [_, quarter] if quarter.starts_with('q') => {
+            let quarter = quarter[1..]
+                .parse::<u32>()
+                .ok()
+                .filter(|q| (1..=4).contains(q))?;
+            Some(quarter_of(NaiveDate::from_ymd_opt(year, quarter * 3, 1)?))
+        }
+        [_, month] => Some(month_of(NaiveDate::from_ymd_opt(
+            year,
+            month.parse().ok()?,
+            1,
+        )?)),
+        [_, month, day] => {
+            let date = NaiveDate::from_ymd_opt(year, month.parse().ok()?, day.parse().ok()?)?;
+            Some((date, date))
+        }
+        _ => None,
+    }
+}
+
+fn year_of(date: NaiveDate) -> (NaiveDate, NaiveDate) {
+    (
+        NaiveDate::from_ymd(date.year(), 1, 1),
+        NaiveDate::from_ymd(date.year(), 12, 31),
+    )
+}
+
+fn quarter_of(date: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let first_month = (date.month0() / 3) * 3 + 1;
+    let begin = NaiveDate::from_ymd(date.year(), first_month, 1);
+    let (_, end) = month_of(NaiveDate::from_ymd(date.year(), first_month + 2, 1));
+
+    (begin, end)
+}
+
+fn month_of(date: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let begin = NaiveDate::from_ymd(date.year(), date.month(), 1);
+    let end = shift_months(begin, 1).pred();
+
+    (begin, end)
+}
+
+/// Moves a date by whole months, landing on the first of the month.
+fn shift_months(date: NaiveDate, months: i32) -> NaiveDate {
+    let months = date.year() * 12 + date.month0() as i32 + months;
+    NaiveDate::from_ymd(months.div_euclid(12), months.rem_euclid(12) as u32 + 1, 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(period: &str, today: Date<Utc>) -> (Date<Utc>, Date<Utc>) {
+        let range = DateRange::period(period, today).unwrap();
+        (range.begin().unwrap(), range.end().unwrap())
+    }
+
+    #[test]
+    fn parses_absolute_periods() {
+        let today = Utc.ymd(2026, 10, 18);
+        assert_eq!(
+            range("2026", today),
+            (Utc.ymd(2026, 1, 1), Utc.ymd(2026, 12, 31))
+        );
+        assert_eq!(
+            range("2024-Q1", today),
+            (Utc.ymd(2024, 1, 1), Utc.ymd(2024, 3, 31))
+        );
+        assert_eq!(
+            range("2024-02", today),
+            (Utc.ymd(2024, 2, 1), Utc.ymd(2024, 2, 29))
+        );
+        assert_eq!(
+            range("2026-09-14", today),
+            (Utc.ymd(2026, 9, 14), Utc.ymd(2026, 9, 14))
+        );
+    }
+
+    #[test]
+    fn parses_relative_periods() {
+        // A Sunday, so the week runs from the Monday before.
+        let today = Utc.ymd(2026, 10, 18);
+        assert_eq!(range("today", today), (today, today));
+        assert_eq!(
+            range("yesterday", today),
+            (Utc.ymd(2026, 10, 17), Utc.ymd(2026, 10, 17))
+        );
+        assert_eq!(
+            range("this week", today),
+            (Utc.ymd(2026, 10, 12), Utc.ymd(2026, 10, 18))
+        );
+        assert_eq!(
+            range("Last  Month", today),
+            (Utc.ymd(2026, 9, 1), Utc.ymd(2026, 9, 30))
+        );
+        assert_eq!(
+            range("next quarter", today),
+            (Utc.ymd(2027, 1, 1), Utc.ymd(2027, 3, 31))
+        );
+        assert_eq!(
+            range("last year", today),
+            (Utc.ymd(2025, 1, 1), Utc.ymd(2025, 12, 31))
+        );
+        assert_eq!(range("qtd", today), (Utc.ymd(2026, 10, 1), today));
+        assert_eq!(range("ytd", today), (Utc.ymd(2026, 1, 1), today));
+    }
+
+    #[test]
+    fn crosses_year_boundaries() {
+        let today = Utc.ymd(2026, 1, 15);
+        assert_eq!(
+            range("last month", today),
+            (Utc.ymd(2025, 12, 1), Utc.ymd(2025, 12, 31))
+        );
+        assert_eq!(
+            range("last quarter", today),
+            (Utc.ymd(2025, 10, 1), Utc.ymd(2025, 12, 31))
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_periods() {
+        let today = Utc.ymd(2026, 10, 18);
+        for period in [
+            "",
+            "soon",
+            "last fortnight",
+            "2026-Q5",
+            "2026-13",
+            "2026-02-30",
+        ] {
+            assert!(DateRange::period(period, today).is_err(), "{}", period);
+        }
+    }
+}
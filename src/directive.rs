@@ -0,0 +1,128 @@
+use std::fmt::Display;
+
+use chrono::{Date, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    account_declaration::{AccountClosing, AccountOpening},
+    commodity::{Commodities, Commodity},
+    currency::Currency,
+    error::Error,
+    price::Price,
+    reconciliation::Reconciliation,
+    transaction::split_with_columns,
+};
+
+/// Anything other than a transaction that can be recorded in a ledger file.
+///
+/// Directives are written on a single line that starts with a date and a
+/// keyword in place of a transaction's id, so that older versions of the
+/// ledger format stay unambiguous.
+///
+/// New variants go at the end, since binary ledger files store each
+/// directive by the index of its variant.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Directive {
+    Commodity(Commodity),
+    Currency(Currency),
+    Price(Price),
+    Reconciliation(Reconciliation),
+    AccountOpening(AccountOpening),
+    AccountClosing(AccountClosing),
+}
+
+impl Directive {
+    /// Whether a ledger file block starting with `header` holds a directive
+    /// rather than a transaction.
+    pub fn is_directive(header: &str) -> bool {
+        matches!(
+            split_with_columns(header).get(1),
+            Some((_, keyword)) if [
+                Commodity::KEYWORD,
+                Currency::KEYWORD,
+                Price::KEYWORD,
+                Reconciliation::KEYWORD,
+                AccountOpening::KEYWORD,
+                AccountClosing::KEYWORD,
+            ]
+            .contains(keyword)
+        )
+    }
+
+    pub fn date(&self) -> Date<Utc> {
+        match self {
+            Directive::Commodity(commodity) => commodity.date(),
+            Directive::Currency(currency) => currency.date(),
+            Directive::Price(price) => price.date(),
+            Directive::Reconciliation(reconciliation) => reconciliation.date(),
+            Directive::AccountOpening(opening) => opening.date(),
+            Directive::AccountClosing(closing) => closing.date(),
+        }
+    }
+
+    /// Parses a directive block from a ledger file. Errors carry the
+    /// 1-indexed line number within `block`, like [`Transaction::parse`].
+    ///
+    /// [`Transaction::parse`]: crate::Transaction::parse
+    pub fn parse(block: &str) -> Result<Self, Vec<Error>> {
+        Self::parse_with(block, &Commodities::new())
+    }
+
+    /// Parses a directive block, reading amounts in the ledger's currency
+    /// format and the precision of their commodities.
+    pub fn parse_with(block: &str, commodities: &Commodities) -> Result<Self, Vec<Error>> {
+        let mut lines = block
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty());
+
+        let (header_idx, header) = lines.next().unwrap_or_default();
+        let keyword = split_with_columns(header)
+            .get(1)
+            .map(|(_, keyword)| *keyword)
+            .unwrap_or_default();
+        let directive = match keyword {
+            Commodity::KEYWORD => Commodity::try_from(header).map(Directive::Commodity),
+            Currency::KEYWORD => Currency::try_from(header).map(Directive::Currency),
+            Price::KEYWORD => Price::parse(header, commodities).map(Directive::Price),
+            AccountOpening::KEYWORD => {
+                AccountOpening::try_from(header).map(Directive::AccountOpening)
+            }
+            AccountClosing::KEYWORD => {
+                AccountClosing::try_from(header).map(Directive::AccountClosing)
+            }
+            _ => Reconciliation::parse(header, commodities).map(Directive::Reconciliation),
+        }
+        .map_err(|err| err.at_line(header_idx + 1, header));
+
+        let mut errors = lines
+            .map(|(idx, line)| {
+                Error::invalid_directive("directives can't have indented lines")
+                    .at_columns(0..line.trim_end().len())
+                    .at_line(idx + 1, line)
+            })
+            .collect::<Vec<Error>>();
+
+        match directive {
+            Ok(directive) if errors.is_empty() => Ok(directive),
+            Ok(_) => Err(errors),
+            Err(err) => {
+                errors.insert(0, err);
+                Err(errors)
+            }
+        }
+    }
+}
+
+impl Display for Directive {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Directive::Commodity(commodity) => commodity.fmt(f),
+            Directive::Currency(currency) => currency.fmt(f),
+            Directive::Price(price) => price.fmt(f),
+            Directive::Reconciliation(reconciliation) => reconciliation.fmt(f),
+            Directive::AccountOpening(opening) => opening.fmt(f),
+            Directive::AccountClosing(closing) => closing.fmt(f),
+        }
+    }
+}
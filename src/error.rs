@@ -1,12 +1,14 @@
-use std::{error, fmt, num::ParseIntError, path::PathBuf};
+use std::{error, fmt, num::ParseIntError, ops::Range, path::PathBuf};
 
-use chrono::ParseError;
-use ulid::DecodeError;
+use chrono::{Date, ParseError, Utc};
+use getset::{CopyGetters, Getters};
+use ulid::{DecodeError, Ulid};
 use ErrorKind::*;
 
 #[derive(Debug)]
 pub struct Error {
     kind: ErrorKind,
+    location: Option<Box<SourceLocation>>,
 }
 
 impl Error {
@@ -14,10 +16,30 @@ impl Error {
         Self::new(LedgerFileNotFound(path))
     }
 
+    pub fn account_already_declared(account: &str) -> Self {
+        Self::new(AccountAlreadyDeclared(account.to_owned()))
+    }
+
+    pub fn account_not_open(account: &str, date: Date<Utc>) -> Self {
+        Self::new(AccountNotOpen(account.to_owned(), date))
+    }
+
+    pub fn ambiguous_transaction_id(prefix: &str, matches: Vec<Ulid>) -> Self {
+        Self::new(AmbiguousTransactionId(prefix.to_owned(), matches))
+    }
+
     pub fn bincode(inner: bincode::Error) -> Self {
         Self::new(BincodeError(inner))
     }
 
+    pub fn duplicate_transaction_id(id: Ulid) -> Self {
+        Self::new(DuplicateTransactionId(id))
+    }
+
+    pub fn empty_account_name() -> Self {
+        Self::new(EmptyAccountName)
+    }
+
     pub fn blank_entry_value() -> Self {
         Self::new(BlankEntryValue)
     }
@@ -26,6 +48,30 @@ impl Error {
         Self::new(CorruptedLedgerFile)
     }
 
+    pub fn invalid_amount(amount: &str, reason: &str) -> Self {
+        Self::new(InvalidAmount(amount.to_owned(), reason.to_owned()))
+    }
+
+    pub fn invalid_account_type(account_type: &str) -> Self {
+        Self::new(InvalidAccountType(account_type.to_owned()))
+    }
+
+    pub fn invalid_account_pattern(pattern: &str, reason: &str) -> Self {
+        Self::new(InvalidAccountPattern(pattern.to_owned(), reason.to_owned()))
+    }
+
+    pub fn insufficient_lots(account: &str, commodity: &str) -> Self {
+        Self::new(InsufficientLots(account.to_owned(), commodity.to_owned()))
+    }
+
+    pub fn invalid_cost(reason: &str) -> Self {
+        Self::new(InvalidCost(reason.to_owned()))
+    }
+
+    pub fn invalid_directive(reason: &str) -> Self {
+        Self::new(InvalidDirective(reason.to_owned()))
+    }
+
     pub fn invalid_entry_format(entry: &str) -> Self {
         Self::new(InvalidEntryFormat(entry.to_owned()))
     }
@@ -38,18 +84,46 @@ impl Error {
         Self::new(InvalidLedgerFile(path))
     }
 
+    pub fn invalid_ledger_format(format: &str) -> Self {
+        Self::new(InvalidLedgerFormat(format.to_owned()))
+    }
+
+    pub fn invalid_lot_method(method: &str) -> Self {
+        Self::new(InvalidLotMethod(method.to_owned()))
+    }
+
+    pub fn invalid_period(period: &str) -> Self {
+        Self::new(InvalidPeriod(period.to_owned()))
+    }
+
     pub fn invalid_transaction_date(error: ParseError) -> Self {
         Self::new(InvalidTransactionDate(error))
     }
 
+    pub fn invalid_transaction_description(reason: &str) -> Self {
+        Self::new(InvalidTransactionDescription(reason.to_owned()))
+    }
+
     pub fn invalid_transaction_id(error: DecodeError) -> Self {
         Self::new(InvalidTransactionId(error))
     }
 
+    pub fn invalid_transaction_status(status: &str) -> Self {
+        Self::new(InvalidTransactionStatus(status.to_owned()))
+    }
+
     pub fn io(inner: std::io::Error) -> Self {
         Self::new(Io(inner))
     }
 
+    pub fn ledger_locked(path: PathBuf) -> Self {
+        Self::new(LedgerLocked(path))
+    }
+
+    pub fn multiple(errors: Vec<Error>) -> Self {
+        Self::new(Multiple(errors))
+    }
+
     pub fn missing_transaction_entries() -> Self {
         Self::new(MissingTransactionEntries)
     }
@@ -58,12 +132,158 @@ impl Error {
         Self::new(MissingTransactionField(field.to_owned()))
     }
 
+    pub fn outdated_ledger_version(version: u32) -> Self {
+        Self::new(OutdatedLedgerVersion(version))
+    }
+
+    pub fn round_trip_mismatch(reason: &str) -> Self {
+        Self::new(RoundTripMismatch(reason.to_owned()))
+    }
+
+    pub fn transaction_not_found(id: &str) -> Self {
+        Self::new(TransactionNotFound(id.to_owned()))
+    }
+
     pub fn unbalanced_transaction_entries() -> Self {
         Self::new(UnbalancedTransactionEntries)
     }
 
+    pub fn undeclared_account(account: &str) -> Self {
+        Self::new(UndeclaredAccount(account.to_owned()))
+    }
+
+    pub fn unsupported_ledger_version(version: u32) -> Self {
+        Self::new(UnsupportedLedgerVersion(version))
+    }
+
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    pub fn location(&self) -> Option<&SourceLocation> {
+        self.location.as_deref()
+    }
+
+    /// Points the error at a range of bytes within the offending line.
+    pub(crate) fn at_columns(mut self, columns: Range<usize>) -> Self {
+        self.location.get_or_insert_with(Default::default).columns = columns;
+        self
+    }
+
+    /// Shifts the error's columns, for errors found in a slice of a line.
+    pub(crate) fn offset_columns(mut self, offset: usize) -> Self {
+        if let Some(location) = self.location.as_mut() {
+            location.columns = location.columns.start + offset..location.columns.end + offset;
+        }
+
+        self
+    }
+
+    /// Attaches the offending line, 1-indexed, to the error. If no columns
+    /// were given, the whole line is underlined.
+    pub(crate) fn at_line(mut self, line: usize, text: &str) -> Self {
+        let location = self.location.get_or_insert_with(Default::default);
+        if location.columns.is_empty() {
+            let start = text.len() - text.trim_start().len();
+            location.columns = start..text.trim_end().len().max(start + 1);
+        }
+
+        location.line = line;
+        location.text = text.to_owned();
+        self
+    }
+
+    /// Shifts the error's line number, for errors found in a slice of a
+    /// larger file.
+    pub(crate) fn offset_lines(mut self, offset: usize) -> Self {
+        if let Some(location) = self.location.as_mut() {
+            location.line += offset;
+        }
+
+        self
+    }
+
+    /// Records the file the error was found in, if it has a location.
+    pub fn in_file(mut self, path: PathBuf) -> Self {
+        if let Some(location) = self.location.as_mut() {
+            location.path = Some(path);
+        }
+
+        self
+    }
+
     fn new(kind: ErrorKind) -> Self {
-        Self { kind }
+        Self {
+            kind,
+            location: None,
+        }
+    }
+}
+
+/// Where in a ledger file an error was found.
+#[derive(Clone, CopyGetters, Debug, Default, Getters)]
+pub struct SourceLocation {
+    #[getset(get = "pub")]
+    path: Option<PathBuf>,
+
+    /// The 1-indexed line number.
+    #[getset(get_copy = "pub")]
+    line: usize,
+
+    /// The byte range within the line that the error refers to.
+    #[getset(get = "pub")]
+    columns: Range<usize>,
+
+    /// The full text of the offending line.
+    #[getset(get = "pub")]
+    text: String,
+}
+
+impl SourceLocation {
+    /// The 1-indexed column the error starts at, counted in characters.
+    pub fn column(&self) -> usize {
+        self.text
+            .char_indices()
+            .take_while(|(idx, _)| *idx < self.columns.start)
+            .count()
+            + 1
+    }
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let gutter = self.line.to_string().len();
+        let mut start = self.columns.start.min(self.text.len());
+        while !self.text.is_char_boundary(start) {
+            start -= 1;
+        }
+
+        let mut end = self.columns.end.clamp(start, self.text.len());
+        while !self.text.is_char_boundary(end) {
+            end += 1;
+        }
+
+        let padding = self.text[..start]
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+        let underline = "^".repeat(self.text[start..end].chars().count().max(1));
+
+        match &self.path {
+            Some(path) => writeln!(
+                f,
+                "{:gutter$}--> {}:{}:{}",
+                "",
+                path.display(),
+                self.line,
+                self.column()
+            )?,
+            None => writeln!(f, "{:gutter$}--> {}:{}", "", self.line, self.column())?,
+        }
+
+        writeln!(f, "{:gutter$} |", "")?;
+        writeln!(f, "{} | {}", self.line, self.text)?;
+        write!(f, "{:gutter$} | {}{}", "", padding, underline)
     }
 }
 
@@ -80,9 +300,54 @@ impl error::Error for Error {
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match &self.kind {
+            AccountAlreadyDeclared(account) => {
+                write!(f, "Account '{}' has already been declared.", account)
+            }
+            AccountNotOpen(account, date) => write!(
+                f,
+                "Account '{}' isn't open on {}.",
+                account,
+                date.format("%Y-%m-%d")
+            ),
+            AmbiguousTransactionId(prefix, matches) => {
+                write!(
+                    f,
+                    "Transaction id '{}' is ambiguous; it matches {} transactions:",
+                    prefix,
+                    matches.len()
+                )?;
+                for id in matches {
+                    write!(f, "\n  {}", id)?;
+                }
+
+                Ok(())
+            }
             BincodeError(err) => write!(f, "{}", err),
             BlankEntryValue => write!(f, "Entry has a blank value."),
             CorruptedLedgerFile => write!(f, "Ledger file contains corrupted data."),
+            DuplicateTransactionId(id) => {
+                write!(f, "Transaction id {} is used more than once.", id)
+            }
+            EmptyAccountName => write!(f, "Entry has an empty account name."),
+            InsufficientLots(account, commodity) => write!(
+                f,
+                "Not enough {} held in the open lots of {} to cover the sale.",
+                commodity, account
+            ),
+            InvalidAccountPattern(pattern, reason) => {
+                write!(f, "Invalid account pattern '{}': {}.", pattern, reason)
+            }
+            InvalidAccountType(account_type) => write!(
+                f,
+                "Invalid account type: '{}'. Expected one of asset, liability, equity, income or \
+                 expense.",
+                account_type
+            ),
+            InvalidAmount(amount, reason) => {
+                write!(f, "Invalid amount '{}': {}.", amount, reason)
+            }
+            InvalidCost(reason) => write!(f, "Invalid cost or price: {}.", reason),
+            InvalidDirective(reason) => write!(f, "Invalid directive: {}.", reason),
             InvalidEntryFormat(entry) => write!(
                 f,
                 "Invalid entry transaction entry: '{}'. Must be in ACCOUNT=VALUE format.",
@@ -90,15 +355,77 @@ impl fmt::Display for Error {
             ),
             InvalidEntryValue(value) => write!(f, "Invalid entry value: {}.", value),
             InvalidLedgerFile(dir) => write!(f, "Invalid ledger directory: {:?}.", dir),
-            InvalidTransactionDate(err) => write!(f, "{}", err),
-            InvalidTransactionId(err) => write!(f, "{}", err),
+            InvalidLedgerFormat(format) => write!(
+                f,
+                "Invalid ledger format: '{}'. Expected text or binary.",
+                format
+            ),
+            InvalidLotMethod(method) => write!(
+                f,
+                "Invalid lot method: '{}'. Expected fifo or lifo.",
+                method
+            ),
+            InvalidPeriod(period) => write!(
+                f,
+                "Invalid period: '{}'. Try a year, quarter, month or date like 2026, 2026-Q3, \
+                 2026-09 or 2026-09-14, 'this month', 'last year' or 'ytd'.",
+                period
+            ),
+            InvalidTransactionDate(err) => write!(f, "Invalid transaction date: {}.", err),
+            InvalidTransactionDescription(reason) => {
+                write!(f, "Invalid transaction description: {}.", reason)
+            }
+            InvalidTransactionId(err) => write!(f, "Invalid transaction id: {}.", err),
+            InvalidTransactionStatus(status) => write!(
+                f,
+                "Invalid transaction status: '{}'. Expected one of uncleared, pending, \
+                 cleared or reconciled.",
+                status
+            ),
             Io(err) => write!(f, "{}", err),
             LedgerFileNotFound(path) => write!(f, "Ledger file not found at: {:?}.", path),
+            LedgerLocked(path) => write!(
+                f,
+                "Ledger file at {:?} is locked by another process; try again shortly.",
+                path
+            ),
             MissingTransactionEntries => write!(f, "Missing entries in transaction."),
             MissingTransactionField(field) => {
                 write!(f, "Transaction is missing the following: {}.", field)
             }
+            Multiple(errors) => {
+                for error in errors {
+                    writeln!(f, "{}\n", error)?;
+                }
+
+                return write!(f, "Found {} errors in the ledger file.", errors.len());
+            }
+            OutdatedLedgerVersion(version) => write!(
+                f,
+                "Ledger file is in version {} of the format; run `sixp migrate` to upgrade it.",
+                version
+            ),
+            RoundTripMismatch(reason) => write!(
+                f,
+                "Ledger file doesn't read back the way sixp writes it: {}.",
+                reason
+            ),
+            TransactionNotFound(id) => write!(f, "No transaction found with id '{}'.", id),
             UnbalancedTransactionEntries => write!(f, "A transaction has unbalanced entries."),
+            UndeclaredAccount(account) => {
+                write!(f, "Account '{}' hasn't been declared.", account)
+            }
+            UnsupportedLedgerVersion(version) => write!(
+                f,
+                "Ledger file is in version {} of the format, which this version of sixp can't \
+                 read.",
+                version
+            ),
+        }?;
+
+        match &self.location {
+            Some(location) => write!(f, "\n{}", location),
+            None => Ok(()),
         }
     }
 }
@@ -139,9 +466,9 @@ impl From<ParseIntError> for Error {
     }
 }
 
-impl Into<i32> for Error {
-    fn into(self) -> i32 {
-        match self.kind {
+impl From<Error> for i32 {
+    fn from(error: Error) -> Self {
+        match error.kind {
             BincodeError(_) => 1,
             BlankEntryValue => 2,
             CorruptedLedgerFile => 3,
@@ -155,23 +482,69 @@ impl Into<i32> for Error {
             MissingTransactionEntries => 11,
             MissingTransactionField(_) => 12,
             UnbalancedTransactionEntries => 13,
+            Multiple(_) => 14,
+            InvalidTransactionDescription(_) => 15,
+            LedgerLocked(_) => 16,
+            InvalidAccountPattern(_, _) => 17,
+            InvalidPeriod(_) => 18,
+            TransactionNotFound(_) => 19,
+            AmbiguousTransactionId(_, _) => 20,
+            InvalidTransactionStatus(_) => 21,
+            InvalidDirective(_) => 22,
+            InsufficientLots(_, _) => 23,
+            InvalidCost(_) => 24,
+            InvalidLotMethod(_) => 25,
+            InvalidAmount(_, _) => 26,
+            UnsupportedLedgerVersion(_) => 27,
+            InvalidLedgerFormat(_) => 28,
+            DuplicateTransactionId(_) => 29,
+            EmptyAccountName => 30,
+            UndeclaredAccount(_) => 31,
+            RoundTripMismatch(_) => 32,
+            OutdatedLedgerVersion(_) => 33,
+            AccountNotOpen(_, _) => 34,
+            AccountAlreadyDeclared(_) => 35,
+            InvalidAccountType(_) => 36,
         }
     }
 }
 
 #[derive(Debug)]
 pub enum ErrorKind {
+    AccountAlreadyDeclared(String),
+    AccountNotOpen(String, Date<Utc>),
+    AmbiguousTransactionId(String, Vec<Ulid>),
     BincodeError(bincode::Error),
     BlankEntryValue,
     CorruptedLedgerFile,
+    DuplicateTransactionId(Ulid),
+    EmptyAccountName,
+    InsufficientLots(String, String),
+    InvalidAccountPattern(String, String),
+    InvalidAccountType(String),
+    InvalidAmount(String, String),
+    InvalidCost(String),
+    InvalidDirective(String),
     InvalidEntryFormat(String),
     InvalidEntryValue(ParseIntError),
     InvalidLedgerFile(PathBuf),
+    InvalidLedgerFormat(String),
+    InvalidLotMethod(String),
+    InvalidPeriod(String),
     InvalidTransactionDate(ParseError),
+    InvalidTransactionDescription(String),
     InvalidTransactionId(DecodeError),
+    InvalidTransactionStatus(String),
     Io(std::io::Error),
     LedgerFileNotFound(PathBuf),
+    LedgerLocked(PathBuf),
     MissingTransactionEntries,
     MissingTransactionField(String),
+    Multiple(Vec<Error>),
+    OutdatedLedgerVersion(u32),
+    RoundTripMismatch(String),
+    TransactionNotFound(String),
     UnbalancedTransactionEntries,
+    UndeclaredAccount(String),
+    UnsupportedLedgerVersion(u32),
 }
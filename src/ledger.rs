@@ -0,0 +1,327 @@
+use std::{
+    fs::{DirBuilder, OpenOptions},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use getset::{CopyGetters, Getters, Setters};
+use ulid::Ulid;
+
+use crate::{
+    account::Account,
+    account_declaration::AccountDeclarations,
+    account_pattern::AccountFilter,
+    amount::Amounts,
+    balances::Balances,
+    commodity::Commodities,
+    date_range::DateRange,
+    directive::Directive,
+    error::Error,
+    lots::Lots,
+    price::Prices,
+    reconciliation::Reconciliation,
+    register::{register, Posting},
+    services::{read_ledger, reconcile_accounts, LedgerFormat, LedgerLock, ParseMode},
+    status::Status,
+    transaction::Transaction,
+};
+
+/// A ledger file loaded into memory.
+///
+/// Transactions are always kept sorted by date; changes only reach the disk
+/// once [`Ledger::save`] is called.
+#[derive(CopyGetters, Debug, Getters, Setters)]
+pub struct Ledger {
+    #[getset(get = "pub")]
+    path: PathBuf,
+
+    #[getset(get = "pub")]
+    transactions: Vec<Transaction>,
+
+    /// Everything else recorded in the ledger, sorted by date.
+    #[getset(get = "pub")]
+    directives: Vec<Directive>,
+
+    /// How many previous versions of the ledger file to keep around as
+    /// backups when saving. Defaults to none.
+    #[getset(get_copy = "pub", set = "pub")]
+    backups: usize,
+
+    /// The format the ledger file is saved in, which is the one it was read
+    /// from unless changed.
+    #[getset(get_copy = "pub", set = "pub")]
+    format: LedgerFormat,
+
+    lock: Option<LedgerLock>,
+}
+
+impl Ledger {
+    /// Creates a new, empty ledger file at `path`, along with any missing
+    /// parent directories. An existing file at `path` is left untouched.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let parent = path
+            .parent()
+            .ok_or_else(|| Error::invalid_ledger_file(path.to_owned()))?;
+        DirBuilder::new().recursive(true).create(parent)?;
+
+        OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)?;
+
+        Self::open(path)
+    }
+
+    /// Loads every transaction from the ledger file at `path`, stopping at
+    /// the first malformed one.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::open_with(path, ParseMode::FailFast)
+    }
+
+    /// Loads every transaction from the ledger file at `path`, handling
+    /// malformed transactions according to `mode`.
+    pub fn open_with<P: AsRef<Path>>(path: P, mode: ParseMode) -> Result<Self, Error> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Err(Error::ledger_file_not_found(path.to_owned()));
+        }
+
+        let format = LedgerFormat::detect(path)?;
+        let (mut transactions, mut directives) = read_ledger(path, mode)?;
+        transactions.sort();
+        directives.sort_by_key(Directive::date);
+
+        Ok(Self {
+            path: path.to_owned(),
+            transactions,
+            directives,
+            backups: 0,
+            format,
+            lock: None,
+        })
+    }
+
+    /// Loads the ledger file at `path` for modification.
+    ///
+    /// An exclusive lock is taken on the ledger before it's read and held
+    /// until the `Ledger` is dropped, so that other processes doing the same
+    /// can't interleave their changes with ours. If another process holds the
+    /// lock for longer than `timeout`, this fails with
+    /// [`ErrorKind::LedgerLocked`](crate::ErrorKind::LedgerLocked).
+    pub fn open_locked<P: AsRef<Path>>(
+        path: P,
+        mode: ParseMode,
+        timeout: Duration,
+    ) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let lock = LedgerLock::acquire(path, timeout)?;
+        let mut ledger = Self::open_with(path, mode)?;
+        ledger.lock = Some(lock);
+
+        Ok(ledger)
+    }
+
+    /// Whether this ledger holds the exclusive lock on its file.
+    pub fn is_locked(&self) -> bool {
+        self.lock.is_some()
+    }
+
+    /// Adds a transaction to the ledger, keeping the transactions sorted.
+    /// Every entry has to name an account.
+    pub fn add_transaction(&mut self, transaction: Transaction) -> Result<(), Error> {
+        if transaction
+            .entries()
+            .iter()
+            .any(|entry| entry.account().trim().is_empty())
+        {
+            return Err(Error::empty_account_name());
+        }
+
+        let idx = self
+            .transactions
+            .partition_point(|existing| existing <= &transaction);
+        self.transactions.insert(idx, transaction);
+
+        Ok(())
+    }
+
+    /// Swaps out the transaction with the same id as `transaction` for it,
+    /// returning the old one. The new one is checked as
+    /// [`Ledger::add_transaction`] does.
+    pub fn replace_transaction(&mut self, transaction: Transaction) -> Result<Transaction, Error> {
+        let idx = self
+            .transactions
+            .iter()
+            .position(|existing| existing.id() == transaction.id())
+            .ok_or_else(|| Error::transaction_not_found(transaction.id().to_string().as_str()))?;
+
+        let replaced = self.transactions.remove(idx);
+        if let Err(err) = self.add_transaction(transaction) {
+            self.transactions.insert(idx, replaced);
+            return Err(err);
+        }
+
+        Ok(replaced)
+    }
+
+    /// Changes the status of the transaction with the given id.
+    pub fn set_status(&mut self, id: Ulid, status: Status) -> Result<(), Error> {
+        let transaction = self
+            .transactions
+            .iter_mut()
+            .find(|transaction| transaction.id() == id)
+            .ok_or_else(|| Error::transaction_not_found(id.to_string().as_str()))?;
+        transaction.set_status(status);
+
+        Ok(())
+    }
+
+    /// Adds a directive to the ledger, after any others with the same date.
+    pub fn add_directive(&mut self, directive: Directive) {
+        let idx = self
+            .directives
+            .partition_point(|existing| existing.date() <= directive.date());
+        self.directives.insert(idx, directive);
+    }
+
+    /// Returns every reconciliation recorded for `account`, oldest first.
+    pub fn reconciliations<'a>(
+        &'a self,
+        account: &'a str,
+    ) -> impl Iterator<Item = &'a Reconciliation> {
+        self.directives
+            .iter()
+            .filter_map(move |directive| match directive {
+                Directive::Reconciliation(reconciliation)
+                    if reconciliation.account() == account =>
+                {
+                    Some(reconciliation)
+                }
+                _ => None,
+            })
+    }
+
+    /// Takes the transaction with the given id out of the ledger, returning
+    /// it.
+    pub fn remove_transaction(&mut self, id: Ulid) -> Result<Transaction, Error> {
+        let idx = self
+            .transactions
+            .iter()
+            .position(|existing| existing.id() == id)
+            .ok_or_else(|| Error::transaction_not_found(id.to_string().as_str()))?;
+
+        Ok(self.transactions.remove(idx))
+    }
+
+    /// Looks up a single transaction by its id.
+    pub fn transaction(&self, id: Ulid) -> Option<&Transaction> {
+        self.transactions
+            .iter()
+            .find(|transaction| transaction.id() == id)
+    }
+
+    /// Looks up a single transaction by its full id, or any prefix of it
+    /// that's unique within the ledger. Case is ignored.
+    pub fn find_transaction(&self, id: &str) -> Result<&Transaction, Error> {
+        let prefix = id.trim().to_uppercase();
+        let matches = self
+            .transactions
+            .iter()
+            .filter(|transaction| transaction.id().to_string().starts_with(prefix.as_str()))
+            .collect::<Vec<&Transaction>>();
+
+        match matches.as_slice() {
+            [] => Err(Error::transaction_not_found(id)),
+            [transaction] => Ok(transaction),
+            _ if prefix.is_empty() => Err(Error::transaction_not_found(id)),
+            _ => Err(Error::ambiguous_transaction_id(
+                id,
+                matches.iter().map(|transaction| transaction.id()).collect(),
+            )),
+        }
+    }
+
+    /// Returns every transaction dated within `range`.
+    pub fn transactions_in(&self, range: DateRange) -> impl Iterator<Item = &Transaction> {
+        self.transactions
+            .iter()
+            .filter(move |transaction| range.contains(transaction.date()))
+    }
+
+    /// Lists every entry within `range` posted to an account matching
+    /// `filter`, oldest first, with a running balance.
+    pub fn register(&self, filter: &AccountFilter, range: DateRange) -> Vec<Posting<'_>> {
+        register(self.transactions_in(range), filter)
+    }
+
+    /// Returns every transaction that has an entry posted to `account`.
+    pub fn transactions_for_account<'a>(
+        &'a self,
+        account: &'a str,
+    ) -> impl Iterator<Item = &'a Transaction> {
+        self.transactions.iter().filter(move |transaction| {
+            transaction
+                .entries()
+                .iter()
+                .any(|entry| entry.account() == account)
+        })
+    }
+
+    /// Computes the balance of every account in the ledger.
+    pub fn accounts(&self) -> Vec<Account> {
+        reconcile_accounts(self.transactions.as_slice())
+    }
+
+    /// Aggregates the balance of every account in the ledger.
+    pub fn balances(&self) -> Balances {
+        self.transactions.iter().collect()
+    }
+
+    /// Computes the balance of a single account in every commodity.
+    pub fn balance(&self, account: &str) -> Amounts {
+        self.balances()
+            .get(account)
+            .map(|account| account.balance().clone())
+            .unwrap_or_default()
+    }
+
+    /// The precision of every commodity declared in the ledger, and the
+    /// latest format of its home currency.
+    pub fn commodities(&self) -> Commodities {
+        Commodities::from_directives(self.directives.iter())
+    }
+
+    /// Every account declared in the ledger, with the days it's open.
+    pub fn account_declarations(&self) -> AccountDeclarations {
+        self.directives.iter().collect()
+    }
+
+    /// Every price recorded in the ledger.
+    pub fn prices(&self) -> Prices {
+        self.directives
+            .iter()
+            .filter_map(|directive| match directive {
+                Directive::Price(price) => Some(price),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The lots still open in every account.
+    pub fn lots(&self) -> Lots {
+        self.transactions.iter().collect()
+    }
+
+    /// Atomically replaces the ledger file with the current transactions,
+    /// rotating backups first if any were asked for.
+    pub fn save(&self) -> Result<(), Error> {
+        self.format.write(
+            self.path.as_path(),
+            self.transactions.as_slice(),
+            self.directives.as_slice(),
+            self.backups,
+        )
+    }
+}
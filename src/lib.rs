@@ -0,0 +1,60 @@
+//! Sixpence is a plain-text, double-entry accounting library.
+//!
+//! The [`Ledger`] type is the main entry point: it loads a ledger file from
+//! disk, lets you add and query [`Transaction`]s, computes [`Account`]
+//! balances and writes everything back out again.
+//!
+//! ```no_run
+//! use sixpence::Ledger;
+//!
+//! # fn main() -> Result<(), sixpence::Error> {
+//! let ledger = Ledger::open("ledger.dat")?;
+//! let commodities = ledger.commodities();
+//!
+//! for account in ledger.accounts() {
+//!     println!("{} {}", account.name(), account.format_balance(&commodities));
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod account;
+pub mod account_declaration;
+pub mod account_pattern;
+pub mod amount;
+pub mod balances;
+pub mod commodity;
+pub mod currency;
+pub mod date_range;
+pub mod directive;
+pub mod error;
+pub mod ledger;
+pub mod lots;
+pub mod price;
+pub mod reconciliation;
+pub mod register;
+mod serde_date;
+pub mod services;
+pub mod status;
+pub mod transaction;
+
+pub use account::Account;
+pub use account_declaration::{
+    AccountClosing, AccountDeclaration, AccountDeclarations, AccountOpening, AccountType,
+};
+pub use account_pattern::{AccountFilter, AccountPattern};
+pub use amount::{Amount, Amounts};
+pub use balances::Balances;
+pub use commodity::{Commodities, Commodity};
+pub use currency::{format_currency, format_quantity, Currency, CurrencyFormat};
+pub use date_range::DateRange;
+pub use directive::Directive;
+pub use error::{Error, ErrorKind, SourceLocation};
+pub use ledger::Ledger;
+pub use lots::{Lot, LotMethod, Lots};
+pub use price::{Price, Prices};
+pub use reconciliation::Reconciliation;
+pub use register::Posting;
+pub use services::{LedgerFormat, LedgerLock, ParseMode};
+pub use status::Status;
+pub use transaction::{DraftEntry, Entry, Transaction};
@@ -0,0 +1,352 @@
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
+
+use chrono::{Date, Utc};
+use getset::{CopyGetters, Getters};
+
+use crate::{
+    amount::{divide_rounded, Amount},
+    commodity::Commodities,
+    error::Error,
+    transaction::{DraftEntry, Entry, Transaction},
+};
+
+/// Which lots a sale draws down first when it doesn't name one by its cost or
+/// date.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum LotMethod {
+    /// The oldest lots first.
+    #[default]
+    Fifo,
+
+    /// The newest lots first.
+    Lifo,
+}
+
+impl FromStr for LotMethod {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "fifo" => Ok(Self::Fifo),
+            "lifo" => Ok(Self::Lifo),
+            _ => Err(Error::invalid_lot_method(s)),
+        }
+    }
+}
+
+impl Display for LotMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Fifo => write!(f, "fifo"),
+            Self::Lifo => write!(f, "lifo"),
+        }
+    }
+}
+
+/// Units of a commodity bought together at the same cost, and still held.
+#[derive(Clone, CopyGetters, Debug, Eq, Getters, PartialEq)]
+pub struct Lot {
+    #[getset(get = "pub")]
+    account: String,
+
+    /// The date the units were bought.
+    #[getset(get_copy = "pub")]
+    date: Date<Utc>,
+
+    /// The units still held.
+    #[getset(get = "pub")]
+    quantity: Amount,
+
+    /// What each whole unit cost.
+    #[getset(get = "pub")]
+    cost: Amount,
+}
+
+impl Lot {
+    /// What the units still held cost in total.
+    pub fn cost_basis(&self, commodities: &Commodities) -> Amount {
+        let units = 10i128.pow(commodities.precision(self.quantity.commodity()));
+        let value = divide_rounded(
+            self.quantity.value() as i128 * self.cost.value() as i128,
+            units,
+        );
+
+        Amount::new(value as isize, self.cost.commodity())
+    }
+
+    fn draw_down(&mut self, value: isize) {
+        self.quantity = Amount::new(self.quantity.value() - value, self.quantity.commodity());
+    }
+}
+
+/// The open lots of every account, per commodity, in the order they were
+/// bought.
+#[derive(Clone, Debug, Default)]
+pub struct Lots {
+    lots: BTreeMap<(String, String), Vec<Lot>>,
+}
+
+impl Lots {
+    pub fn new() -> Self {
+        Self {
+            lots: BTreeMap::new(),
+        }
+    }
+
+    /// Applies an entry with a cost: buying opens a new lot, and selling draws
+    /// down the lots bought at the same cost on the entry's lot date, the
+    /// ones [`book`](Self::book) picked. Sales that don't give a lot date
+    /// draw down the oldest lots bought at the same cost.
+    pub fn post(&mut self, date: Date<Utc>, entry: &Entry) {
+        let cost = match entry.cost() {
+            Some(cost) => cost,
+            None => return,
+        };
+
+        let key = (entry.account().to_owned(), entry.commodity().to_owned());
+        let lots = self.lots.entry(key).or_default();
+        if entry.value() > 0 {
+            lots.push(Lot {
+                account: entry.account().to_owned(),
+                date,
+                quantity: entry.amount().clone(),
+                cost: cost.clone(),
+            });
+            return;
+        }
+
+        let mut remaining = -entry.value();
+        let lot_date = entry.lot_date();
+        for lot in lots
+            .iter_mut()
+            .filter(|lot| lot.cost() == cost && lot_date.is_none_or(|date| lot.date() == date))
+        {
+            let taken = remaining.min(lot.quantity().value());
+            lot.draw_down(taken);
+            remaining -= taken;
+        }
+        lots.retain(|lot| lot.quantity().value() > 0);
+    }
+
+    /// The open lots of a commodity in an account, oldest first.
+    pub fn lots(&self, account: &str, commodity: &str) -> &[Lot] {
+        self.lots
+            .get(&(account.to_owned(), commodity.to_owned()))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Lot> {
+        self.lots.values().flatten()
+    }
+
+    /// Matches every sale among `entries` against the open lots, splitting it
+    /// into one entry per lot it draws on, which records the lot's cost and
+    /// date. A sale is an entry that takes units out of an account at a
+    /// price. One that gives a cost or a lot date sells only from lots bought
+    /// at that cost or on that date; `method` picks among the lots left.
+    /// Sales out of accounts without lots are left alone.
+    pub fn book(
+        &self,
+        entries: &[DraftEntry],
+        method: LotMethod,
+    ) -> Result<Vec<DraftEntry>, Error> {
+        let mut lots = self.clone();
+        let mut booked = Vec::<DraftEntry>::with_capacity(entries.len());
+
+        for entry in entries.iter() {
+            let (amount, price) = match (entry.amount(), entry.price()) {
+                (Some(amount), Some(price)) if amount.value() < 0 => (amount, price),
+                _ => {
+                    booked.push(entry.clone());
+                    continue;
+                }
+            };
+
+            let key = (entry.account().to_owned(), amount.commodity().to_owned());
+            let open = match lots.lots.get_mut(&key) {
+                Some(open) if !open.is_empty() => open,
+                _ if entry.cost().is_none() && entry.lot_date().is_none() => {
+                    booked.push(entry.clone());
+                    continue;
+                }
+                _ => {
+                    return Err(Error::insufficient_lots(
+                        entry.account(),
+                        amount.commodity(),
+                    ))
+                }
+            };
+
+            let mut order = (0..open.len())
+                .filter(|&idx| {
+                    let lot = &open[idx];
+                    entry.cost().as_ref().is_none_or(|cost| lot.cost() == cost)
+                        && entry.lot_date().is_none_or(|date| lot.date() == date)
+                })
+                .collect::<Vec<usize>>();
+            if method == LotMethod::Lifo {
+                order.reverse();
+            }
+
+            let mut remaining = -amount.value();
+            let mut drawn = BTreeMap::<(Amount, Date<Utc>), isize>::new();
+            for idx in order {
+                let lot = &mut open[idx];
+                let taken = remaining.min(lot.quantity().value());
+                lot.draw_down(taken);
+                *drawn.entry((lot.cost().clone(), lot.date())).or_default() += taken;
+
+                remaining -= taken;
+                if remaining == 0 {
+                    break;
+                }
+            }
+            open.retain(|lot| lot.quantity().value() > 0);
+
+            if remaining > 0 {
+                return Err(Error::insufficient_lots(
+                    entry.account(),
+                    amount.commodity(),
+                ));
+            }
+
+            booked.extend(drawn.into_iter().map(|((cost, date), taken)| {
+                DraftEntry::new(
+                    entry.account(),
+                    Some(Amount::new(-taken, amount.commodity())),
+                    Some(cost),
+                    Some(price.clone()),
+                )
+                .with_lot_date(Some(date))
+            }));
+        }
+
+        Ok(booked)
+    }
+}
+
+impl<'a> FromIterator<&'a Transaction> for Lots {
+    /// Replays transactions, which have to come in date order, to find the
+    /// lots still open after them.
+    fn from_iter<I: IntoIterator<Item = &'a Transaction>>(transactions: I) -> Self {
+        let mut lots = Self::new();
+        for transaction in transactions {
+            for entry in transaction.entries().iter() {
+                lots.post(transaction.date(), entry);
+            }
+        }
+
+        lots
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use ulid::Ulid;
+
+    use crate::{status::Status, transaction::validate_and_normalize_entries};
+
+    use super::*;
+
+    fn transaction(date: Date<Utc>, entries: Vec<Entry>) -> Transaction {
+        Transaction::new(date, Ulid::new(), Status::Cleared, String::new(), entries)
+    }
+
+    /// Two lots of 10 AAPL bought at the same cost a month apart.
+    fn purchases() -> Vec<Transaction> {
+        [Utc.ymd(2026, 1, 5), Utc.ymd(2026, 2, 5)]
+            .into_iter()
+            .map(|date| {
+                let entry = Entry::annotated(
+                    "Assets:Brokerage",
+                    Amount::new(1000, "AAPL"),
+                    None,
+                    None,
+                    Some(Amount::of_default(15000)),
+                )
+                .unwrap();
+                transaction(date, vec![entry, Entry::new("Assets:Cash", -150000)])
+            })
+            .collect()
+    }
+
+    fn sell(transactions: &mut Vec<Transaction>, lot_date: Option<Date<Utc>>, method: LotMethod) {
+        let entries = [
+            DraftEntry::new(
+                "Assets:Brokerage",
+                Some(Amount::new(-400, "AAPL")),
+                None,
+                Some(Amount::of_default(17500)),
+            )
+            .with_lot_date(lot_date),
+            DraftEntry::new("Assets:Cash", None, None, None),
+        ];
+
+        let lots = transactions.iter().collect::<Lots>();
+        let entries = lots.book(&entries, method).unwrap();
+        let entries = validate_and_normalize_entries(&entries, &Commodities::new()).unwrap();
+        transactions.push(transaction(Utc.ymd(2026, 3, 5), entries));
+    }
+
+    fn remaining(transactions: &[Transaction]) -> Vec<(Date<Utc>, isize)> {
+        let lots = transactions.iter().collect::<Lots>();
+        lots.lots("Assets:Brokerage", "AAPL")
+            .iter()
+            .map(|lot| (lot.date(), lot.quantity().value()))
+            .collect()
+    }
+
+    #[test]
+    fn sells_from_the_newest_lot_with_lifo() {
+        let mut transactions = purchases();
+        sell(&mut transactions, None, LotMethod::Lifo);
+
+        let sale = transactions.last().unwrap();
+        let sold = sale.entries().iter().find(|entry| entry.is_sale()).unwrap();
+        assert_eq!(sold.lot_date(), Some(Utc.ymd(2026, 2, 5)));
+
+        assert_eq!(
+            remaining(&transactions),
+            vec![(Utc.ymd(2026, 1, 5), 1000), (Utc.ymd(2026, 2, 5), 600)]
+        );
+    }
+
+    #[test]
+    fn sells_from_the_oldest_lot_with_fifo() {
+        let mut transactions = purchases();
+        sell(&mut transactions, None, LotMethod::Fifo);
+
+        assert_eq!(
+            remaining(&transactions),
+            vec![(Utc.ymd(2026, 1, 5), 600), (Utc.ymd(2026, 2, 5), 1000)]
+        );
+    }
+
+    #[test]
+    fn sells_from_the_lot_bought_on_a_date() {
+        let mut transactions = purchases();
+        sell(
+            &mut transactions,
+            Some(Utc.ymd(2026, 1, 5)),
+            LotMethod::Lifo,
+        );
+
+        assert_eq!(
+            remaining(&transactions),
+            vec![(Utc.ymd(2026, 1, 5), 600), (Utc.ymd(2026, 2, 5), 1000)]
+        );
+    }
+
+    #[test]
+    fn reads_back_the_lot_a_sale_drew_down() {
+        let mut transactions = purchases();
+        sell(&mut transactions, None, LotMethod::Lifo);
+
+        let sale = transactions.last().unwrap();
+        let text = sale.to_string();
+        assert!(text.contains("\tAssets:Brokerage -400 AAPL {15000, 2026-02-05} @ 17500"));
+        assert_eq!(&Transaction::try_from(text.clone()).unwrap(), sale);
+    }
+}
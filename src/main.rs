@@ -1,36 +1,32 @@
 use std::process;
 
-use accounting::Accounting;
 use clap::Parser;
 use cli::{Cli, Commands};
-use lazy_static::lazy_static;
 
-mod account;
 mod cli;
 mod commands;
-mod error;
-mod services;
-mod transaction;
-
-lazy_static! {
-    static ref ACCOUNTING: Accounting = Accounting::new("$", 2, ",", ".", "{v}", "({v})", "—");
-}
 
 fn main() {
     let cli = Cli::parse();
 
-    let ledger_file_path = match cli.global_args().ledger_file() {
-        Ok(path) => path,
-        Err(err) => {
-            eprintln!("{}", err);
-            process::exit(err.into())
-        }
-    };
+    let global_args = cli.global_args();
 
     let result = match cli.commands() {
-        Commands::New => commands::new_ledger(ledger_file_path.as_path()),
-        Commands::Accounts(opts) => commands::display_accounts(ledger_file_path.as_path(), opts),
-        Commands::Transaction(opts) => commands::new_transaction(ledger_file_path.as_path(), opts),
+        Commands::New(opts) => commands::new_ledger(global_args, opts),
+        Commands::Convert(opts) => commands::convert(global_args, opts),
+        Commands::Check(opts) => commands::check(global_args, opts),
+        Commands::Migrate => commands::migrate(global_args),
+        Commands::Accounts(opts) => commands::display_accounts(global_args, opts),
+        Commands::Account(opts) => commands::account(global_args, opts),
+        Commands::Register(opts) => commands::register(global_args, opts),
+        Commands::Show(opts) => commands::show_transaction(global_args, opts),
+        Commands::Edit(opts) => commands::edit_transaction(global_args, opts),
+        Commands::Remove(opts) => commands::remove_transaction(global_args, opts),
+        Commands::Void(opts) => commands::void_transaction(global_args, opts),
+        Commands::Prices(opts) => commands::prices(global_args, opts),
+        Commands::Lots(opts) => commands::lots(global_args, opts),
+        Commands::Reconcile(opts) => commands::reconcile(global_args, opts),
+        Commands::Transaction(opts) => commands::new_transaction(global_args, opts),
     };
 
     if let Err(err) = result {
@@ -0,0 +1,287 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use chrono::{Date, Utc};
+use getset::{CopyGetters, Getters};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    amount::{divide_rounded, is_commodity_symbol, split_trailing_amount, Amount, Amounts},
+    commodity::Commodities,
+    error::Error,
+    transaction::{parse_date, split_with_columns},
+};
+
+/// The price of one whole unit of a commodity on a given day, written to the
+/// ledger as `YYYY-MM-DD price <symbol> <price>`, e.g. `2026-09-30 price
+/// AAPL 22750` for a share worth $227.50.
+#[derive(Clone, CopyGetters, Debug, Deserialize, Eq, Getters, PartialEq, Serialize)]
+pub struct Price {
+    #[getset(get_copy = "pub")]
+    #[serde(with = "crate::serde_date")]
+    date: Date<Utc>,
+
+    #[getset(get = "pub")]
+    commodity: String,
+
+    /// What one unit of the commodity is worth, in another commodity.
+    #[getset(get = "pub")]
+    price: Amount,
+}
+
+impl Price {
+    pub const KEYWORD: &'static str = "price";
+
+    pub fn new(date: Date<Utc>, commodity: &str, price: Amount) -> Result<Self, Error> {
+        if !is_commodity_symbol(commodity) {
+            return Err(Error::invalid_directive("invalid commodity symbol"));
+        }
+
+        if price.commodity() == commodity {
+            return Err(Error::invalid_directive(
+                "a commodity can't be priced in itself",
+            ));
+        }
+
+        Ok(Self {
+            date,
+            commodity: commodity.to_owned(),
+            price,
+        })
+    }
+
+    /// Parses a price directive, reading the price in the precision of its
+    /// commodity.
+    pub fn parse(line: &str, commodities: &Commodities) -> Result<Self, Error> {
+        let parts = split_with_columns(line);
+        let end = line.trim_end().len();
+        if parts.len() < 4 {
+            return Err(
+                Error::invalid_directive("a price needs a commodity and a price")
+                    .at_columns(end..end + 1),
+            );
+        }
+
+        let (date_columns, date) = &parts[0];
+        let date = parse_date(date, date_columns.clone())?;
+
+        let (commodity_columns, commodity) = &parts[2];
+        let (start, price) = split_trailing_amount(&parts[3..], commodities)?;
+        if start != parts[3].0.start {
+            return Err(Error::invalid_directive("unexpected text before the price")
+                .at_columns(parts[3].0.start..start));
+        }
+
+        Self::new(date, commodity, price).map_err(|err| err.at_columns(commodity_columns.clone()))
+    }
+}
+
+impl TryFrom<&str> for Price {
+    type Error = Error;
+
+    fn try_from(line: &str) -> Result<Self, Self::Error> {
+        Self::parse(line, &Commodities::new())
+    }
+}
+
+impl Display for Price {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.date.format("%Y-%m-%d"),
+            Self::KEYWORD,
+            self.commodity,
+            self.price
+        )
+    }
+}
+
+/// Every price recorded in a ledger, for converting amounts between
+/// commodities.
+#[derive(Clone, Debug, Default)]
+pub struct Prices {
+    /// Prices by the commodity they're for and the commodity they're in,
+    /// sorted by date.
+    prices: BTreeMap<(String, String), BTreeMap<Date<Utc>, isize>>,
+}
+
+impl Prices {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a price. A later price on the same date replaces an earlier
+    /// one.
+    pub fn add(&mut self, price: &Price) {
+        let history = self
+            .prices
+            .entry((
+                price.commodity().to_owned(),
+                price.price().commodity().to_owned(),
+            ))
+            .or_default();
+
+        history.insert(price.date(), price.price().value());
+    }
+
+    /// The latest price of one unit of `commodity` in `target`, recorded on
+    /// or before `date`.
+    pub fn price(&self, commodity: &str, target: &str, date: Date<Utc>) -> Option<Amount> {
+        let history = self
+            .prices
+            .get(&(commodity.to_owned(), target.to_owned()))?;
+        history
+            .range(..=date)
+            .next_back()
+            .map(|(_, value)| Amount::new(*value, target))
+    }
+
+    /// Converts `amount` into `target` at the latest price on or before
+    /// `date`, rounding to the nearest unit of `target`. When only `target`
+    /// has been priced in `amount`'s commodity, that price is inverted.
+    /// Amounts already in `target`, or without a known price, are returned
+    /// unchanged.
+    pub fn convert(
+        &self,
+        amount: &Amount,
+        target: &str,
+        date: Date<Utc>,
+        commodities: &Commodities,
+    ) -> Amount {
+        if amount.commodity() == target {
+            return amount.clone();
+        }
+
+        let value = amount.value() as i128;
+        let converted = match self.price(amount.commodity(), target, date) {
+            Some(price) => divide_rounded(
+                value * price.value() as i128,
+                10i128.pow(commodities.precision(amount.commodity())),
+            ),
+            None => match self.price(target, amount.commodity(), date) {
+                Some(price) if price.value() != 0 => divide_rounded(
+                    value * 10i128.pow(commodities.precision(target)),
+                    price.value() as i128,
+                ),
+                _ => return amount.clone(),
+            },
+        };
+
+        Amount::new(converted as isize, target)
+    }
+
+    /// Converts every commodity in `amounts` into `target`, as with
+    /// [`Prices::convert`].
+    pub fn convert_all(
+        &self,
+        amounts: &Amounts,
+        target: &str,
+        date: Date<Utc>,
+        commodities: &Commodities,
+    ) -> Amounts {
+        amounts
+            .iter()
+            .map(|amount| self.convert(&amount, target, date, commodities))
+            .collect::<Vec<Amount>>()
+            .iter()
+            .collect()
+    }
+
+    /// Every price, ordered by commodity, then the commodity it's in, then
+    /// date.
+    pub fn iter(&self) -> impl Iterator<Item = Price> + '_ {
+        self.prices
+            .iter()
+            .flat_map(|((commodity, target), history)| {
+                history.iter().map(move |(date, value)| Price {
+                    date: *date,
+                    commodity: commodity.to_owned(),
+                    price: Amount::new(*value, target),
+                })
+            })
+    }
+}
+
+impl<'a> FromIterator<&'a Price> for Prices {
+    fn from_iter<I: IntoIterator<Item = &'a Price>>(prices: I) -> Self {
+        let mut recorded = Self::new();
+        for price in prices {
+            recorded.add(price);
+        }
+
+        recorded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use crate::commodity::DEFAULT_COMMODITY;
+
+    use super::*;
+
+    /// A euro worth $1.10 from the 5th of October, and $1.20 from the 8th.
+    fn prices() -> Prices {
+        [(5, 110), (8, 120)]
+            .into_iter()
+            .map(|(day, value)| {
+                Price::new(Utc.ymd(2026, 10, day), "EUR", Amount::of_default(value)).unwrap()
+            })
+            .collect::<Vec<Price>>()
+            .iter()
+            .collect()
+    }
+
+    #[test]
+    fn converts_at_the_latest_price_on_or_before_the_date() {
+        let prices = prices();
+        let commodities = Commodities::new();
+        let euros = Amount::new(1000, "EUR");
+
+        let converted = prices.convert(
+            &euros,
+            DEFAULT_COMMODITY,
+            Utc.ymd(2026, 10, 7),
+            &commodities,
+        );
+        assert_eq!(converted, Amount::of_default(1100));
+
+        let converted = prices.convert(
+            &euros,
+            DEFAULT_COMMODITY,
+            Utc.ymd(2026, 10, 8),
+            &commodities,
+        );
+        assert_eq!(converted, Amount::of_default(1200));
+    }
+
+    #[test]
+    fn converts_at_the_inverse_of_the_target_price() {
+        let prices = prices();
+        let commodities = Commodities::new();
+        let date = Utc.ymd(2026, 10, 5);
+
+        let converted = prices.convert(&Amount::of_default(1100), "EUR", date, &commodities);
+        assert_eq!(converted, Amount::new(1000, "EUR"));
+
+        let converted = prices.convert(&Amount::of_default(1000), "EUR", date, &commodities);
+        assert_eq!(converted, Amount::new(909, "EUR"));
+    }
+
+    #[test]
+    fn leaves_amounts_without_a_price_by_the_date_unchanged() {
+        let prices = prices();
+        let commodities = Commodities::new();
+        let date = Utc.ymd(2026, 10, 4);
+
+        let euros = Amount::new(1000, "EUR");
+        assert_eq!(
+            prices.convert(&euros, DEFAULT_COMMODITY, date, &commodities),
+            euros
+        );
+
+        let dollars = Amount::of_default(1000);
+        assert_eq!(prices.convert(&dollars, "EUR", date, &commodities), dollars);
+    }
+}
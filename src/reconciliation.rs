@@ -0,0 +1,95 @@
+use std::fmt::Display;
+
+use chrono::{Date, Utc};
+use getset::{CopyGetters, Getters};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    amount::{split_trailing_amount, Amount},
+    commodity::Commodities,
+    error::Error,
+    transaction::{parse_date, split_with_columns},
+};
+
+/// A record of an account having been reconciled against a bank statement,
+/// written to the ledger as `YYYY-MM-DD reconciled <account> <balance>`.
+#[derive(Clone, CopyGetters, Debug, Deserialize, Eq, Getters, PartialEq, Serialize)]
+pub struct Reconciliation {
+    /// The date of the statement.
+    #[getset(get_copy = "pub")]
+    #[serde(with = "crate::serde_date")]
+    date: Date<Utc>,
+
+    #[getset(get = "pub")]
+    account: String,
+
+    /// The closing balance on the statement.
+    #[getset(get = "pub")]
+    balance: Amount,
+}
+
+impl Reconciliation {
+    pub const KEYWORD: &'static str = "reconciled";
+
+    pub fn new(date: Date<Utc>, account: &str, balance: Amount) -> Self {
+        Self {
+            date,
+            account: account.to_owned(),
+            balance,
+        }
+    }
+
+    /// Parses a reconciliation directive, reading the balance in the
+    /// precision of its commodity.
+    pub fn parse(line: &str, commodities: &Commodities) -> Result<Self, Error> {
+        let parts = split_with_columns(line);
+        let end = line.trim_end().len();
+        if parts.len() < 2 {
+            return Err(
+                Error::invalid_directive("expected a date and a keyword").at_columns(end..end + 1)
+            );
+        }
+
+        let (date_columns, date) = &parts[0];
+        let date = parse_date(date, date_columns.clone())?;
+
+        let (keyword_columns, _) = &parts[1];
+        // The account always takes up at least one part, even when its
+        // name could pass for an amount.
+        let (start, balance) = match parts.len() {
+            0..=2 => {
+                return Err(Error::invalid_directive(
+                    "a reconciliation needs an account and balance",
+                )
+                .at_columns(end..end + 1))
+            }
+            3 => return Err(Error::blank_entry_value().at_columns(end..end + 1)),
+            _ => split_trailing_amount(&parts[3..], commodities)?,
+        };
+
+        let account = line[keyword_columns.end..start].trim();
+
+        Ok(Self::new(date, account, balance))
+    }
+}
+
+impl TryFrom<&str> for Reconciliation {
+    type Error = Error;
+
+    fn try_from(line: &str) -> Result<Self, Self::Error> {
+        Self::parse(line, &Commodities::new())
+    }
+}
+
+impl Display for Reconciliation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.date.format("%Y-%m-%d"),
+            Self::KEYWORD,
+            self.account,
+            self.balance
+        )
+    }
+}
@@ -0,0 +1,53 @@
+use getset::CopyGetters;
+
+use crate::{
+    account_pattern::AccountFilter,
+    amount::Amounts,
+    transaction::{Entry, Transaction},
+};
+
+/// A single entry as it appears in a register, alongside the transaction it
+/// belongs to.
+#[derive(Clone, Copy, CopyGetters, Debug)]
+pub struct Posting<'t> {
+    #[getset(get_copy = "pub")]
+    transaction: &'t Transaction,
+
+    #[getset(get_copy = "pub")]
+    entry: &'t Entry,
+
+    /// The sum of this posting and every one before it in the register, in
+    /// this posting's commodity.
+    #[getset(get_copy = "pub")]
+    running_balance: isize,
+}
+
+/// Lists every entry posted to an account matching `filter`, in the order
+/// the transactions are given, with a running balance.
+pub fn register<'t, I>(transactions: I, filter: &AccountFilter) -> Vec<Posting<'t>>
+where
+    I: IntoIterator<Item = &'t Transaction>,
+{
+    let mut running_balances = Amounts::new();
+
+    transactions
+        .into_iter()
+        .flat_map(|transaction| {
+            transaction
+                .entries()
+                .iter()
+                .map(move |entry| (transaction, entry))
+        })
+        .filter(|(_, entry)| filter.matches(entry.account()))
+        .map(|(transaction, entry)| {
+            running_balances.add(entry.amount());
+            let running_balance = running_balances.get(entry.commodity());
+
+            Posting {
+                transaction,
+                entry,
+                running_balance,
+            }
+        })
+        .collect()
+}
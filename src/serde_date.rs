@@ -0,0 +1,42 @@
+//! Serializes dates as the number of days since the start of the common era,
+//! since chrono only implements serde for naive dates.
+
+use chrono::{Date, Datelike, NaiveDate, TimeZone, Utc};
+use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+pub fn serialize<S: Serializer>(date: &Date<Utc>, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_i32(date.num_days_from_ce())
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Date<Utc>, D::Error> {
+    from_days(i32::deserialize(deserializer)?)
+}
+
+fn from_days<E: Error>(days: i32) -> Result<Date<Utc>, E> {
+    let date = NaiveDate::from_num_days_from_ce_opt(days)
+        .ok_or_else(|| E::custom(format!("{} days is out of range for a date", days)))?;
+
+    Ok(Utc.from_utc_date(&date))
+}
+
+/// The same, for dates that may be left out.
+pub mod option {
+    use chrono::{Date, Datelike, Utc};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        date: &Option<Date<Utc>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        date.map(|date| date.num_days_from_ce())
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Date<Utc>>, D::Error> {
+        Option::<i32>::deserialize(deserializer)?
+            .map(super::from_days)
+            .transpose()
+    }
+}
@@ -0,0 +1,270 @@
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{ErrorKind, Read},
+    path::Path,
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{directive::Directive, error::Error, transaction::Transaction};
+
+use super::write_transactions::{replace_ledger_file, write_ledger};
+
+/// The bytes every binary ledger file starts with.
+pub const BINARY_LEDGER_MAGIC: &[u8; 8] = b"SIXPENCE";
+
+/// The version of the binary ledger format written by this version of
+/// sixpence. It's bumped whenever the layout of anything serialized changes.
+pub const BINARY_LEDGER_VERSION: u32 = 2;
+
+/// How a ledger file is stored on disk.
+///
+/// The text format is the one meant to be read, edited and exchanged; the
+/// binary format holds exactly the same data but loads much faster, which
+/// matters for large ledgers.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum LedgerFormat {
+    #[default]
+    Text,
+    Binary,
+}
+
+impl LedgerFormat {
+    /// The file extension that marks a new ledger file as binary.
+    pub const BINARY_EXTENSION: &'static str = "bin";
+
+    /// The format of the ledger file at `path`, going by what's in it. Empty
+    /// or missing files go by their extension instead.
+    pub fn detect(path: &Path) -> Result<Self, Error> {
+        let mut magic = [0u8; BINARY_LEDGER_MAGIC.len()];
+        let read = match File::open(path) {
+            Ok(mut file) => read_up_to(&mut file, &mut magic)?,
+            Err(err) if err.kind() == ErrorKind::NotFound => 0,
+            Err(err) => return Err(err.into()),
+        };
+
+        match read {
+            0 => Ok(Self::for_path(path)),
+            _ if &magic == BINARY_LEDGER_MAGIC => Ok(Self::Binary),
+            _ => Ok(Self::Text),
+        }
+    }
+
+    /// The format a new ledger file at `path` is written in, going by its
+    /// extension.
+    pub fn for_path(path: &Path) -> Self {
+        match path.extension() {
+            Some(extension) if extension == Self::BINARY_EXTENSION => Self::Binary,
+            _ => Self::Text,
+        }
+    }
+
+    /// Writes every transaction and directive to the ledger file in this
+    /// format, replacing its contents as [`write_ledger`] does.
+    pub fn write(
+        self,
+        ledger_file_path: &Path,
+        transactions: &[Transaction],
+        directives: &[Directive],
+        backups: usize,
+    ) -> Result<(), Error> {
+        match self {
+            Self::Text => write_ledger(ledger_file_path, transactions, directives, backups),
+            Self::Binary => {
+                write_binary_ledger(ledger_file_path, transactions, directives, backups)
+            }
+        }
+    }
+}
+
+impl FromStr for LedgerFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "binary" => Ok(Self::Binary),
+            _ => Err(Error::invalid_ledger_format(s)),
+        }
+    }
+}
+
+impl Display for LedgerFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Text => write!(f, "text"),
+            Self::Binary => write!(f, "binary"),
+        }
+    }
+}
+
+/// What follows the header of a binary ledger file. Transactions are
+/// borrowed when writing, so that they needn't be cloned.
+#[derive(Serialize)]
+struct Contents<'a> {
+    transactions: Vec<&'a Transaction>,
+    directives: &'a [Directive],
+}
+
+#[derive(Deserialize)]
+struct OwnedContents {
+    transactions: Vec<Transaction>,
+    directives: Vec<Directive>,
+}
+
+/// Reads every transaction and directive from the contents of a binary
+/// ledger file, in the order they were written.
+pub(crate) fn decode_binary_ledger(
+    contents: &[u8],
+) -> Result<(Vec<Transaction>, Vec<Directive>), Error> {
+    let header_len = BINARY_LEDGER_MAGIC.len() + 4;
+    if contents.len() < header_len || !contents.starts_with(BINARY_LEDGER_MAGIC) {
+        return Err(Error::corrupted_ledger_file());
+    }
+
+    let mut version = [0u8; 4];
+    version.copy_from_slice(&contents[BINARY_LEDGER_MAGIC.len()..header_len]);
+    let version = u32::from_le_bytes(version);
+    if version != BINARY_LEDGER_VERSION {
+        return Err(Error::unsupported_ledger_version(version));
+    }
+
+    let contents = bincode::deserialize::<OwnedContents>(&contents[header_len..])?;
+
+    Ok((contents.transactions, contents.directives))
+}
+
+/// Writes every transaction and directive to the ledger file in the binary
+/// format, replacing its contents the same way as
+/// [`write_transactions`](super::write_transactions).
+pub fn write_binary_ledger(
+    ledger_file_path: &Path,
+    transactions: &[Transaction],
+    directives: &[Directive],
+    backups: usize,
+) -> Result<(), Error> {
+    let contents = encode_binary_ledger(transactions.iter().collect(), directives)?;
+
+    replace_ledger_file(ledger_file_path, contents.as_slice(), backups)
+}
+
+pub(crate) fn encode_binary_ledger(
+    transactions: Vec<&Transaction>,
+    directives: &[Directive],
+) -> Result<Vec<u8>, Error> {
+    let mut contents = Vec::<u8>::from(&BINARY_LEDGER_MAGIC[..]);
+    contents.extend_from_slice(&BINARY_LEDGER_VERSION.to_le_bytes());
+    bincode::serialize_into(
+        &mut contents,
+        &Contents {
+            transactions,
+            directives,
+        },
+    )?;
+
+    Ok(contents)
+}
+
+fn read_up_to(file: &mut File, buffer: &mut [u8]) -> Result<usize, Error> {
+    let mut read = 0;
+    while read < buffer.len() {
+        match file.read(&mut buffer[read..])? {
+            0 => break,
+            n => read += n,
+        }
+    }
+
+    Ok(read)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use ulid::Ulid;
+
+    use crate::{
+        error::ErrorKind,
+        services::{read_ledger, write_transactions::encode_ledger, ParseMode},
+    };
+
+    use super::*;
+
+    const LEDGER: &str = "\
+sixpence ledger v2
+
+2026-01-01 commodity AAPL 2
+
+2026-01-01 open Assets:Brokerage type=asset \"Shares\"
+
+2026-01-05 01J0000000000000000000000A \"Buy\"
+\tAssets:Brokerage 1000 AAPL @ 15000
+\tAssets:Cash -150000
+
+2026-01-05 price AAPL 15000
+
+2026-02-05 01J0000000000000000000000B * \"Sell\"
+\tAssets:Brokerage -400 AAPL {15000, 2026-01-05} @ 17500
+\tAssets:Cash 70000
+\tIncome:Capital Gains -10000
+
+2026-02-06 01J0000000000000000000000C ! \"Pending\"
+\tAssets:Cash -1000
+\tExpenses:Food 1000
+
+2026-02-07 01J0000000000000000000000D R
+\tAssets:Cash -500
+\tExpenses:Fees 500
+
+2026-02-28 reconciled Assets:Cash -81500
+
+2026-03-01 close Assets:Brokerage";
+
+    /// Encodes the transactions and directives of a text ledger in the binary
+    /// format.
+    fn encode(text: &str) -> (Vec<u8>, Vec<Transaction>, Vec<Directive>) {
+        let path = env::temp_dir().join(format!("sixp-test-{}.txt", Ulid::new()));
+        fs::write(path.as_path(), text).unwrap();
+        let read = read_ledger(path.as_path(), ParseMode::FailFast);
+        fs::remove_file(path).unwrap();
+
+        let (transactions, directives) = read.unwrap();
+        let binary = encode_binary_ledger(transactions.iter().collect(), &directives).unwrap();
+
+        (binary, transactions, directives)
+    }
+
+    #[test]
+    fn converts_to_binary_and_back_without_changes() {
+        let (binary, transactions, directives) = encode(LEDGER);
+        assert_eq!(encode_ledger(transactions.iter(), &directives), LEDGER);
+
+        let (transactions, directives) = decode_binary_ledger(binary.as_slice()).unwrap();
+        assert_eq!(encode_ledger(transactions.iter(), &directives), LEDGER);
+    }
+
+    #[test]
+    fn rejects_files_without_the_magic_number() {
+        let (mut binary, _, _) = encode(LEDGER);
+        binary[0] = b'Z';
+
+        let err = decode_binary_ledger(binary.as_slice()).unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::CorruptedLedgerFile));
+    }
+
+    #[test]
+    fn rejects_unsupported_versions() {
+        let (mut binary, _, _) = encode(LEDGER);
+        let version = BINARY_LEDGER_VERSION + 1;
+        binary[BINARY_LEDGER_MAGIC.len()..BINARY_LEDGER_MAGIC.len() + 4]
+            .copy_from_slice(&version.to_le_bytes());
+
+        let err = decode_binary_ledger(binary.as_slice()).unwrap_err();
+        assert!(matches!(
+            err.kind(),
+            ErrorKind::UnsupportedLedgerVersion(v) if *v == version
+        ));
+    }
+}
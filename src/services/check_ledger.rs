@@ -0,0 +1,270 @@
+use std::{
+    collections::{BTreeSet, HashSet},
+    fs,
+    path::Path,
+};
+
+use ulid::Ulid;
+
+use crate::{
+    account_declaration::{AccountClosing, AccountDeclarations, AccountOpening},
+    commodity::Commodities,
+    directive::Directive,
+    error::Error,
+    transaction::{split_with_columns, Transaction},
+};
+
+use super::{
+    binary_ledger::{decode_binary_ledger, BINARY_LEDGER_MAGIC},
+    ledger_version::{parse_ledger_header, LEDGER_VERSION},
+    read_transactions::{declared_commodities, into_string, transaction_blocks},
+    write_transactions::encode_ledger,
+};
+
+/// Checks everything in the ledger file at `path`, returning every problem
+/// found rather than stopping at the first.
+///
+/// Besides what reading the ledger already catches, like dates that don't
+/// parse, this checks that every transaction balances, that no two share an
+/// id, that no entry has an empty account name, and that the file reads back
+/// exactly the way sixp would write it. When `declared_accounts` is given,
+/// every account posted to has to be among them. When `strict`, every
+/// account posted to has to have been opened in the ledger, and not yet
+/// closed. Problems in a text ledger point at the line they were found on.
+pub fn check_ledger(
+    ledger_file_path: &Path,
+    declared_accounts: Option<&BTreeSet<String>>,
+    strict: bool,
+) -> Result<Vec<Error>, Error> {
+    let bytes = fs::read(ledger_file_path)?;
+    if bytes.starts_with(BINARY_LEDGER_MAGIC) {
+        let (transactions, directives) = match decode_binary_ledger(bytes.as_slice()) {
+            Ok(contents) => contents,
+            Err(err) => return Ok(vec![err]),
+        };
+        let commodities = Commodities::from_directives(directives.iter());
+        let account_declarations = strict.then(|| directives.iter().collect());
+        let mut checks =
+            TransactionChecks::new(&commodities, declared_accounts, account_declarations);
+
+        return Ok(transactions
+            .iter()
+            .flat_map(|transaction| checks.check(transaction))
+            .map(|(_, err)| err)
+            .collect());
+    }
+
+    let contents = match into_string(ledger_file_path, bytes) {
+        Ok(contents) => contents,
+        Err(err) => return Ok(vec![err]),
+    };
+
+    let mut findings = Vec::<Error>::new();
+    let mut blocks = transaction_blocks(contents.as_str());
+    let version = match blocks.first() {
+        Some((0, header)) => match parse_ledger_header(header.trim_end()) {
+            Some(version) => {
+                blocks.remove(0);
+                version.map_err(|err| err.in_file(ledger_file_path.to_owned()))?
+            }
+            None => 1,
+        },
+        _ => 1,
+    };
+    if version < LEDGER_VERSION {
+        findings.push(Error::outdated_ledger_version(version));
+    }
+
+    let commodities = declared_commodities(blocks.as_slice());
+    let account_declarations = strict.then(|| account_declarations(blocks.as_slice()));
+    let mut checks = TransactionChecks::new(&commodities, declared_accounts, account_declarations);
+    let mut transactions = Vec::<Transaction>::new();
+    let mut directives = Vec::<Directive>::new();
+    let mut can_round_trip = findings.is_empty();
+
+    for (first_line, block) in blocks.iter() {
+        let header = block.lines().next().unwrap_or_default();
+        let mut block_findings = Vec::<Error>::new();
+
+        let written = match Directive::is_directive(header) {
+            true => Directive::parse_with(block.as_str(), &commodities).map(|directive| {
+                let written = directive.to_string();
+                directives.push(directive);
+                written
+            }),
+            false => Transaction::parse_in_version(block.as_str(), &commodities, version).map(
+                |transaction| {
+                    block_findings.extend(checks.check(&transaction).into_iter().map(
+                        |(spot, err)| match spot {
+                            Spot::Header => err.at_line(1, header),
+                            Spot::Id => match split_with_columns(header).get(1) {
+                                Some((columns, _)) => {
+                                    err.at_columns(columns.clone()).at_line(1, header)
+                                }
+                                None => err.at_line(1, header),
+                            },
+                            Spot::Account(idx, len) => {
+                                let line = block.lines().nth(idx + 1).unwrap_or_default();
+                                let start = line.len() - line.trim_start().len();
+                                err.at_columns(start..start + len).at_line(idx + 2, line)
+                            }
+                        },
+                    ));
+
+                    let written = transaction.to_string();
+                    transactions.push(transaction);
+                    written
+                },
+            ),
+        };
+
+        match written {
+            Ok(written) => {
+                let read = block.strip_suffix('\n').unwrap_or(block.as_str());
+                if let Some((line, err)) = first_difference(read, written.as_str()) {
+                    let text = read.lines().nth(line - 1).unwrap_or_default();
+                    block_findings.push(err.at_line(line, text));
+                }
+            }
+            Err(errors) => block_findings.extend(errors),
+        }
+
+        can_round_trip &= block_findings.is_empty();
+        findings.extend(block_findings.into_iter().map(|err| {
+            err.offset_lines(*first_line)
+                .in_file(ledger_file_path.to_owned())
+        }));
+    }
+
+    // Every block reading back the way it's written still leaves their order
+    // and the blank lines between them to check.
+    if can_round_trip {
+        transactions.sort();
+        directives.sort_by_key(Directive::date);
+
+        let written = encode_ledger(transactions.iter(), directives.as_slice());
+        if let Some((line, err)) = first_difference(contents.as_str(), written.as_str()) {
+            let text = contents.lines().nth(line - 1).unwrap_or_default();
+            findings.push(err.at_line(line, text).in_file(ledger_file_path.to_owned()));
+        }
+    }
+
+    Ok(findings)
+}
+
+/// Where in a transaction a problem was found.
+enum Spot {
+    Header,
+    Id,
+
+    /// The account of the entry at an index, and the length of its name.
+    Account(usize, usize),
+}
+
+/// The checks run on each transaction in turn, which remember the ids seen so
+/// far.
+struct TransactionChecks<'a> {
+    commodities: &'a Commodities,
+    declared_accounts: Option<&'a BTreeSet<String>>,
+    account_declarations: Option<AccountDeclarations>,
+    ids: HashSet<Ulid>,
+}
+
+impl<'a> TransactionChecks<'a> {
+    fn new(
+        commodities: &'a Commodities,
+        declared_accounts: Option<&'a BTreeSet<String>>,
+        account_declarations: Option<AccountDeclarations>,
+    ) -> Self {
+        Self {
+            commodities,
+            declared_accounts,
+            account_declarations,
+            ids: HashSet::new(),
+        }
+    }
+
+    fn check(&mut self, transaction: &Transaction) -> Vec<(Spot, Error)> {
+        let mut findings = Vec::<(Spot, Error)>::new();
+
+        if !self.ids.insert(transaction.id()) {
+            findings.push((Spot::Id, Error::duplicate_transaction_id(transaction.id())));
+        }
+
+        if let Err(err) = transaction.validate(self.commodities) {
+            findings.push((Spot::Header, err));
+        }
+
+        for (idx, entry) in transaction.entries().iter().enumerate() {
+            let account = entry.account();
+            let spot = Spot::Account(idx, account.len());
+            if account.is_empty() {
+                findings.push((spot, Error::empty_account_name()));
+            } else if self
+                .declared_accounts
+                .is_some_and(|declared| !declared.contains(account))
+            {
+                findings.push((spot, Error::undeclared_account(account)));
+            } else if let Some(Err(err)) = self
+                .account_declarations
+                .as_ref()
+                .map(|declarations| declarations.check_posting(account, transaction.date()))
+            {
+                findings.push((spot, err));
+            }
+        }
+
+        findings
+    }
+}
+
+/// Reads the account declarations ahead of everything else, since they're
+/// dated after the transactions posted to them on the same day. Malformed
+/// ones are skipped here and reported along with the rest of the file.
+fn account_declarations(blocks: &[(usize, String)]) -> AccountDeclarations {
+    let mut directives = blocks
+        .iter()
+        .filter(|(_, block)| {
+            let header = block.lines().next().unwrap_or_default();
+            matches!(
+                split_with_columns(header).get(1),
+                Some((_, keyword)) if [AccountOpening::KEYWORD, AccountClosing::KEYWORD].contains(keyword)
+            )
+        })
+        .filter_map(|(_, block)| Directive::parse(block.as_str()).ok())
+        .collect::<Vec<Directive>>();
+    directives.sort_by_key(Directive::date);
+
+    directives.iter().collect()
+}
+
+/// Finds the first line where `read` differs from what sixp would have
+/// `written`, returning its 1-indexed number and what's wrong with it.
+fn first_difference(read: &str, written: &str) -> Option<(usize, Error)> {
+    if read == written {
+        return None;
+    }
+
+    let mut read_lines = read.split('\n');
+    let mut written_lines = written.split('\n');
+    let mut line = 0;
+    loop {
+        line += 1;
+        let reason = match (read_lines.next(), written_lines.next()) {
+            (Some(read), Some(written)) if read == written => continue,
+            (Some(_), Some(written)) => format!("sixp writes this line as '{}'", written),
+            (Some(""), None) if read_lines.next().is_none() => {
+                line -= 1;
+                "sixp doesn't end the file with a newline".to_owned()
+            }
+            (Some(_), None) => "sixp doesn't write this line".to_owned(),
+            (None, Some(written)) => {
+                line -= 1;
+                format!("sixp writes '{}' after this line", written)
+            }
+            (None, None) => unreachable!("read and written differ"),
+        };
+
+        return Some((line.max(1), Error::round_trip_mismatch(reason.as_str())));
+    }
+}
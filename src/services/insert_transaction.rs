@@ -0,0 +1,294 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+use chrono::{Date, Utc};
+
+use crate::{
+    directive::Directive,
+    error::Error,
+    transaction::{parse_date, Transaction},
+};
+
+use super::{
+    binary_ledger::{encode_binary_ledger, LedgerFormat},
+    ledger_version::{ledger_version, LEDGER_HEADER_PREFIX, LEDGER_VERSION},
+    read_transactions::{read_ledger, ParseMode},
+    write_transactions::{encode_ledger, replace_ledger_file},
+};
+
+const TAIL_CHUNK_SIZE: u64 = 4096;
+
+/// Adds a single transaction to the ledger file without parsing and
+/// re-serializing the transactions that are already in it.
+///
+/// When the transaction goes after everything in the ledger and no backups
+/// were asked for, it's appended to the end of the file, which only needs to
+/// read the last few kilobytes of the ledger. That isn't atomic, though: a
+/// crash part way through the write can leave part of the transaction at the
+/// end of the file. So when backups are asked for, or the transaction goes
+/// anywhere else, the ledger's header lines are scanned for the right spot,
+/// and the transaction is spliced in there through the same atomic
+/// replacement as [`super::write_transactions`]. Either way it ends up where
+/// [`encode_ledger`] would have written it, ahead of any directives dated the
+/// same day.
+///
+/// Binary ledger files can't be added to in place, and text ones written in
+/// an older version of the format shouldn't be, so they're read in full and
+/// written back out with the transaction among the others.
+pub fn insert_transaction(
+    ledger_file_path: &Path,
+    transaction: &Transaction,
+    backups: usize,
+) -> Result<(), Error> {
+    if !ledger_file_path.exists() {
+        return Err(Error::ledger_file_not_found(ledger_file_path.to_owned()));
+    }
+
+    let format = LedgerFormat::detect(ledger_file_path)?;
+    if format == LedgerFormat::Binary || ledger_version(ledger_file_path)? < LEDGER_VERSION {
+        return rewrite_with_transaction(ledger_file_path, format, transaction, backups);
+    }
+
+    let mut file = OpenOptions::new()
+        .read(true)
+        .append(true)
+        .open(ledger_file_path)?;
+
+    let goes_last = last_header(&mut file).and_then(|last| match last {
+        Some(last) => goes_before(last.as_slice(), transaction.date()).map(|before| !before),
+        None => Ok(true),
+    });
+
+    match goes_last {
+        Ok(true) if backups == 0 => append_transaction(&mut file, transaction),
+        Ok(_) | Err(_) => splice_transaction(ledger_file_path, transaction, backups),
+    }
+}
+
+fn append_transaction(file: &mut File, transaction: &Transaction) -> Result<(), Error> {
+    let len = file.metadata()?.len();
+    let separator = match len {
+        0 => "",
+        _ => {
+            let mut tail = vec![0u8; len.min(2) as usize];
+            file.seek(SeekFrom::End(-(tail.len() as i64)))?;
+            file.read_exact(tail.as_mut_slice())?;
+
+            match tail.iter().rev().take_while(|byte| **byte == b'\n').count() {
+                0 => "\n\n",
+                1 => "\n",
+                _ => "",
+            }
+        }
+    };
+
+    write!(file, "{}{}", separator, transaction)?;
+    file.sync_all()?;
+
+    Ok(())
+}
+
+/// Reads the whole ledger and writes it back out with the transaction among
+/// the others, upgrading text ledgers written in an older version of the
+/// format along the way.
+fn rewrite_with_transaction(
+    ledger_file_path: &Path,
+    format: LedgerFormat,
+    transaction: &Transaction,
+    backups: usize,
+) -> Result<(), Error> {
+    let (mut transactions, mut directives) = read_ledger(ledger_file_path, ParseMode::FailFast)?;
+    transactions.sort();
+    directives.sort_by_key(Directive::date);
+
+    let idx = transactions.partition_point(|existing| existing <= transaction);
+    let mut all = transactions.iter().collect::<Vec<&Transaction>>();
+    all.insert(idx, transaction);
+
+    let contents = match format {
+        LedgerFormat::Text => encode_ledger(all.into_iter(), directives.as_slice()).into_bytes(),
+        LedgerFormat::Binary => encode_binary_ledger(all, directives.as_slice())?,
+    };
+
+    replace_ledger_file(ledger_file_path, contents.as_slice(), backups)
+}
+
+fn splice_transaction(
+    ledger_file_path: &Path,
+    transaction: &Transaction,
+    backups: usize,
+) -> Result<(), Error> {
+    let contents = fs::read(ledger_file_path)?;
+    let offset = insertion_offset(ledger_file_path, contents.as_slice(), transaction.date())?;
+
+    let mut spliced = Vec::<u8>::with_capacity(contents.len() + 256);
+    spliced.extend_from_slice(&contents[..offset]);
+    if offset == contents.len() && offset > 0 {
+        let trailing_newlines = contents.iter().rev().take_while(|byte| **byte == b'\n');
+        spliced.extend(std::iter::repeat_n(
+            b'\n',
+            2 - trailing_newlines.count().min(2),
+        ));
+    }
+
+    spliced.extend_from_slice(transaction.to_string().as_bytes());
+    if offset < contents.len() {
+        spliced.extend_from_slice(b"\n\n");
+        spliced.extend_from_slice(&contents[offset..]);
+    }
+
+    replace_ledger_file(ledger_file_path, spliced.as_slice(), backups)
+}
+
+/// Finds the byte offset of the first transaction or directive that a
+/// transaction dated `date` goes before, or the end of the file if there is
+/// none.
+fn insertion_offset(
+    ledger_file_path: &Path,
+    contents: &[u8],
+    date: Date<Utc>,
+) -> Result<usize, Error> {
+    let mut offset = 0;
+    for (idx, line) in contents.split(|byte| *byte == b'\n').enumerate() {
+        let line_start = offset;
+        offset += line.len() + 1;
+
+        if !is_header(line) {
+            continue;
+        }
+
+        let before = goes_before(line, date).map_err(|err| {
+            err.at_line(idx + 1, String::from_utf8_lossy(line).as_ref())
+                .in_file(ledger_file_path.to_owned())
+        })?;
+
+        if before {
+            return Ok(line_start);
+        }
+    }
+
+    Ok(contents.len())
+}
+
+/// Reads backwards from the end of the ledger file until it finds the header
+/// of the last transaction or directive, and returns it.
+fn last_header(file: &mut File) -> Result<Option<Vec<u8>>, Error> {
+    let mut position = file.metadata()?.len();
+    let mut tail = Vec::<u8>::new();
+
+    while position > 0 {
+        let chunk_size = position.min(TAIL_CHUNK_SIZE);
+        position -= chunk_size;
+
+        let mut chunk = vec![0u8; chunk_size as usize];
+        file.seek(SeekFrom::Start(position))?;
+        file.read_exact(chunk.as_mut_slice())?;
+        chunk.extend_from_slice(tail.as_slice());
+        tail = chunk;
+
+        // Unless we've reached the start of the file, the first line may only
+        // be partially read, so it can't be trusted to be a header.
+        let lines = tail.split(|byte| *byte == b'\n').collect::<Vec<&[u8]>>();
+        let complete_lines = if position == 0 {
+            &lines[..]
+        } else {
+            &lines[1..]
+        };
+        if let Some(header) = complete_lines.iter().rev().find(|line| is_header(line)) {
+            return Ok(Some(header.to_vec()));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Whether `line` starts a transaction or directive, rather than being one of
+/// its entries or the ledger's version header.
+fn is_header(line: &[u8]) -> bool {
+    match line.first() {
+        Some(byte) => {
+            !byte.is_ascii_whitespace() && !line.starts_with(LEDGER_HEADER_PREFIX.as_bytes())
+        }
+        None => false,
+    }
+}
+
+/// Whether a transaction dated `date` goes before the transaction or
+/// directive starting with `header`: directives are written after the
+/// transactions dated the same day.
+fn goes_before(header: &[u8], date: Date<Utc>) -> Result<bool, Error> {
+    let header_date = parse_header_date(header)?;
+
+    Ok(header_date > date
+        || (header_date == date
+            && Directive::is_directive(String::from_utf8_lossy(header).as_ref())))
+}
+
+fn parse_header_date(line: &[u8]) -> Result<Date<Utc>, Error> {
+    let date = line.split(|byte| *byte == b' ').next().unwrap_or_default();
+
+    parse_date(String::from_utf8_lossy(date).as_ref(), 0..date.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use chrono::TimeZone;
+
+    use ulid::Ulid;
+
+    use crate::{
+        amount::Amount, price::Price, services::check_ledger::check_ledger, status::Status,
+        transaction::Entry,
+    };
+
+    use super::*;
+
+    fn transaction(day: u32) -> Transaction {
+        Transaction::new(
+            Utc.ymd(2026, 10, day),
+            Ulid::new(),
+            Status::default(),
+            String::new(),
+            vec![
+                Entry::new("Assets:Cash", -500),
+                Entry::new("Expenses:Food", 500),
+            ],
+        )
+    }
+
+    /// Writes a ledger holding a transaction on the 1st and a price on the
+    /// 5th, inserts a transaction dated `day` into it, and returns what
+    /// `check` finds.
+    fn insert_around_directive(day: u32) -> Vec<Error> {
+        let path = env::temp_dir().join(format!("sixp-test-{}.txt", Ulid::new()));
+        let price = Price::new(Utc.ymd(2026, 10, 5), "EUR", Amount::of_default(105)).unwrap();
+        let directives = [Directive::Price(price)];
+        fs::write(
+            path.as_path(),
+            encode_ledger([transaction(1)].iter(), &directives),
+        )
+        .unwrap();
+
+        insert_transaction(path.as_path(), &transaction(day), 0).unwrap();
+        let findings = check_ledger(path.as_path(), None, false).unwrap();
+        fs::remove_file(path).unwrap();
+
+        findings
+    }
+
+    #[test]
+    fn inserts_ahead_of_a_directive_on_the_same_day() {
+        assert!(insert_around_directive(5).is_empty());
+    }
+
+    #[test]
+    fn inserts_around_directives_on_other_days() {
+        assert!(insert_around_directive(3).is_empty());
+        assert!(insert_around_directive(7).is_empty());
+    }
+}
@@ -0,0 +1,60 @@
+use std::{
+    ffi::OsString,
+    fs::{File, OpenOptions, TryLockError},
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::error::Error;
+
+const RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// An exclusive, advisory lock on a ledger file, released when dropped.
+///
+/// The lock is taken on a `<ledger>.lock` file next to the ledger rather than
+/// the ledger itself, since saving replaces the ledger file with a new one.
+#[derive(Debug)]
+pub struct LedgerLock {
+    _file: File,
+}
+
+impl LedgerLock {
+    /// Locks the ledger file at `ledger_file_path`, waiting up to `timeout`
+    /// for any other process holding the lock to let go of it.
+    pub fn acquire(ledger_file_path: &Path, timeout: Duration) -> Result<Self, Error> {
+        if !ledger_file_path.exists() {
+            return Err(Error::ledger_file_not_found(ledger_file_path.to_owned()));
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(lock_file_path(ledger_file_path))?;
+
+        let started = Instant::now();
+        loop {
+            match file.try_lock() {
+                Ok(()) => return Ok(Self { _file: file }),
+                Err(TryLockError::WouldBlock) if started.elapsed() < timeout => {
+                    thread::sleep(RETRY_INTERVAL.min(timeout.saturating_sub(started.elapsed())));
+                }
+                Err(TryLockError::WouldBlock) => {
+                    return Err(Error::ledger_locked(ledger_file_path.to_owned()))
+                }
+                Err(TryLockError::Error(err)) => return Err(Error::from(err)),
+            }
+        }
+    }
+}
+
+fn lock_file_path(ledger_file_path: &Path) -> PathBuf {
+    let mut file_name = ledger_file_path
+        .file_name()
+        .map(OsString::from)
+        .unwrap_or_default();
+    file_name.push(".lock");
+
+    ledger_file_path.with_file_name(file_name)
+}
@@ -0,0 +1,142 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Read},
+    path::Path,
+};
+
+use crate::error::Error;
+
+use super::binary_ledger::BINARY_LEDGER_MAGIC;
+
+/// The version of the text ledger format written by this version of
+/// sixpence. It's bumped whenever the way transactions or directives are
+/// written changes, and ledgers written in older versions are upgraded as
+/// they're read.
+///
+/// Version 1 ledgers have no header line, only ever mark uncleared
+/// transactions, with `*`, and write descriptions bare, quotes and all.
+pub const LEDGER_VERSION: u32 = 2;
+
+/// What the first line of a text ledger file starts with, followed by the
+/// version of the format the rest of it is written in. Ledgers written before
+/// the line was added are version 1.
+pub const LEDGER_HEADER_PREFIX: &str = "sixpence ledger v";
+
+/// The first line of a text ledger file written in the current version.
+pub fn ledger_header() -> String {
+    format!("{}{}", LEDGER_HEADER_PREFIX, LEDGER_VERSION)
+}
+
+/// The version of the text format the ledger file at `path` is written in,
+/// going by its first line. Binary ledger files can only be read at their own
+/// version, so they're always current.
+pub fn ledger_version(ledger_file_path: &Path) -> Result<u32, Error> {
+    let mut first_line = Vec::<u8>::new();
+    BufReader::new(File::open(ledger_file_path)?)
+        .take(64)
+        .read_until(b'\n', &mut first_line)?;
+
+    if first_line.starts_with(BINARY_LEDGER_MAGIC) {
+        return Ok(LEDGER_VERSION);
+    }
+
+    let first_line = String::from_utf8_lossy(first_line.as_slice());
+    parse_ledger_header(first_line.trim_end())
+        .unwrap_or(Ok(1))
+        .map_err(|err| err.in_file(ledger_file_path.to_owned()))
+}
+
+/// Reads the version from a ledger file's header line, or `None` if `line`
+/// isn't one.
+pub(crate) fn parse_ledger_header(line: &str) -> Option<Result<u32, Error>> {
+    let version = line.strip_prefix(LEDGER_HEADER_PREFIX)?;
+    let columns = LEDGER_HEADER_PREFIX.len()..line.len().max(LEDGER_HEADER_PREFIX.len() + 1);
+
+    let result = match version.trim_end().parse::<u32>() {
+        Ok(version) if (1..=LEDGER_VERSION).contains(&version) => Ok(version),
+        Ok(version) => Err(Error::unsupported_ledger_version(version)),
+        Err(_) => Err(Error::corrupted_ledger_file()),
+    };
+
+    Some(result.map_err(|err| err.at_columns(columns).at_line(1, line)))
+}
+
+/// How many backups to keep when writing the ledger file at `path`, given
+/// that `backups` were asked for. Writing a text ledger in an older version
+/// of the format upgrades it to one older versions of sixp can't read, so the
+/// first time that happens at least one backup is kept.
+pub(crate) fn upgrade_backups(ledger_file_path: &Path, backups: usize) -> Result<usize, Error> {
+    let is_empty = match ledger_file_path.metadata() {
+        Ok(metadata) => metadata.len() == 0,
+        Err(_) => true,
+    };
+
+    match !is_empty && ledger_version(ledger_file_path)? < LEDGER_VERSION {
+        true => Ok(backups.max(1)),
+        false => Ok(backups),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use ulid::Ulid;
+
+    use crate::{ledger::Ledger, services::backup_file_path};
+
+    use super::*;
+
+    #[test]
+    fn migrates_a_ledger_written_by_the_first_version() {
+        let path = env::temp_dir().join(format!("sixp-test-{}.txt", Ulid::new()));
+        let baseline = "\
+2026-01-01 01KBZ7Q1N0S4ZR5EWNXK0G0GZA Opening
+\tAssets:Cash 10000
+\tEquities:Starting Balances -10000
+
+2026-01-02 01KBZ7Q1N0S4ZR5EWNXK0G0GZB * R
+\tAssets:Cash -500
+\tExpenses:Food 500
+
+2026-01-03 01KBZ7Q1N0S4ZR5EWNXK0G0GZC ! \"Tip\" for R&D
+\tAssets:Cash -200
+\tExpenses:Food 200
+
+2026-01-04 01KBZ7Q1N0S4ZR5EWNXK0G0GZD 
+\tAssets:Cash -100
+\tExpenses:Food 100";
+        fs::write(path.as_path(), baseline).unwrap();
+        assert_eq!(ledger_version(path.as_path()).unwrap(), 1);
+
+        Ledger::open(path.as_path()).unwrap().save().unwrap();
+        let migrated = fs::read_to_string(path.as_path()).unwrap();
+        let backup = backup_file_path(path.as_path(), 1);
+        let kept = fs::read_to_string(backup.as_path()).unwrap();
+        fs::remove_file(path).unwrap();
+        fs::remove_file(backup).unwrap();
+
+        assert_eq!(
+            migrated,
+            "\
+sixpence ledger v2
+
+2026-01-01 01KBZ7Q1N0S4ZR5EWNXK0G0GZA \"Opening\"
+\tAssets:Cash 10000
+\tEquities:Starting Balances -10000
+
+2026-01-02 01KBZ7Q1N0S4ZR5EWNXK0G0GZB * \"R\"
+\tAssets:Cash -500
+\tExpenses:Food 500
+
+2026-01-03 01KBZ7Q1N0S4ZR5EWNXK0G0GZC \"! \\\"Tip\\\" for R&D\"
+\tAssets:Cash -200
+\tExpenses:Food 200
+
+2026-01-04 01KBZ7Q1N0S4ZR5EWNXK0G0GZD
+\tAssets:Cash -100
+\tExpenses:Food 100"
+        );
+        assert_eq!(kept, baseline);
+    }
+}
@@ -1,5 +1,19 @@
+mod binary_ledger;
+mod check_ledger;
+mod insert_transaction;
+mod ledger_lock;
+mod ledger_version;
 mod read_transactions;
+mod reconcile_accounts;
 mod write_transactions;
 
-pub use read_transactions::read_transactions;
-pub use write_transactions::write_transactions;
+pub use binary_ledger::{
+    write_binary_ledger, LedgerFormat, BINARY_LEDGER_MAGIC, BINARY_LEDGER_VERSION,
+};
+pub use check_ledger::check_ledger;
+pub use insert_transaction::insert_transaction;
+pub use ledger_lock::LedgerLock;
+pub use ledger_version::{ledger_header, ledger_version, LEDGER_HEADER_PREFIX, LEDGER_VERSION};
+pub use read_transactions::{read_ledger, read_transactions, ParseMode};
+pub use reconcile_accounts::reconcile_accounts;
+pub use write_transactions::{backup_file_path, write_ledger, write_transactions};
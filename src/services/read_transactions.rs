@@ -1,56 +1,152 @@
-use std::{
-    fs::{self, File},
-    io::Read,
-    path::Path,
+use std::{fs, path::Path};
+
+use crate::{
+    commodity::{Commodities, Commodity},
+    currency::Currency,
+    directive::Directive,
+    error::Error,
+    transaction::{split_with_columns, Transaction},
+};
+
+use super::{
+    binary_ledger::{decode_binary_ledger, BINARY_LEDGER_MAGIC},
+    ledger_version::parse_ledger_header,
 };
 
-use crate::{error::Error, transaction::Transaction};
+/// How to react to malformed transactions while reading a ledger file.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ParseMode {
+    /// Stop at the first error.
+    #[default]
+    FailFast,
+
+    /// Keep going, and report every error found in the file at once.
+    CollectAll,
+}
+
+pub fn read_transactions(
+    ledger_file_path: &Path,
+    mode: ParseMode,
+) -> Result<Vec<Transaction>, Error> {
+    read_ledger(ledger_file_path, mode).map(|(transactions, _)| transactions)
+}
+
+/// Reads every transaction and directive from the ledger file, in the order
+/// they appear in it. Binary ledger files are told apart from text ones by
+/// their header, and text ones written in an older version of the format are
+/// upgraded to the current one as they're read.
+pub fn read_ledger(
+    ledger_file_path: &Path,
+    mode: ParseMode,
+) -> Result<(Vec<Transaction>, Vec<Directive>), Error> {
+    let bytes = fs::read(ledger_file_path)?;
+    if bytes.starts_with(BINARY_LEDGER_MAGIC) {
+        return decode_binary_ledger(bytes.as_slice());
+    }
 
-pub fn read_transactions<'p>(ledger_file_path: &'p Path) -> Result<Vec<Transaction>, Error> {
+    let contents = into_string(ledger_file_path, bytes)?;
     let mut transactions = Vec::<Transaction>::new();
-    let mut buffer = Vec::<u8>::with_capacity(64);
-    let mut is_end_of_line = false;
-    let ledger_file = File::open(ledger_file_path)?;
-
-    // WARNING! Clever code incoming!
-    if fs::metadata(ledger_file_path).map_or(false, |metadata| match metadata.len() {
-        0 => false,
-        _ => true,
-    }) {
-        let mut byte_iter = ledger_file.bytes().peekable();
-        while let Some(res) = byte_iter.next() {
-            match res {
-                Ok(byte) => {
-                    if (byte == ('\n' as u8) && is_end_of_line) || byte_iter.peek().is_none() {
-                        if byte_iter.peek().is_none() {
-                            buffer.push(byte);
-                        }
-
-                        match String::from_utf8(buffer.clone())
-                            .map_err(|_| Error::corrupted_ledger_file())
-                            .and_then(Transaction::try_from)
-                        {
-                            Ok(transaction) => {
-                                buffer.clear();
-                                is_end_of_line = false;
-                                transactions.push(transaction);
-                            }
-                            Err(err) => {
-                                return Err(err);
-                            }
-                        }
-                    } else if byte == ('\n' as u8) && !is_end_of_line {
-                        buffer.push(byte);
-                        is_end_of_line = true;
-                    } else {
-                        buffer.push(byte);
-                        is_end_of_line = false;
-                    }
+    let mut directives = Vec::<Directive>::new();
+    let mut errors = Vec::<Error>::new();
+
+    let mut blocks = transaction_blocks(contents.as_str());
+    let version = match blocks.first() {
+        Some((0, header)) => match parse_ledger_header(header.trim_end()) {
+            Some(version) => {
+                blocks.remove(0);
+                version.map_err(|err| err.in_file(ledger_file_path.to_owned()))?
+            }
+            None => 1,
+        },
+        _ => 1,
+    };
+
+    let commodities = declared_commodities(blocks.as_slice());
+    for (first_line, block) in blocks {
+        let header = block.lines().next().unwrap_or_default();
+        let result = match Directive::is_directive(header) {
+            true => Directive::parse_with(block.as_str(), &commodities)
+                .map(|directive| directives.push(directive)),
+            false => Transaction::parse_in_version(block.as_str(), &commodities, version)
+                .map(|transaction| transactions.push(transaction)),
+        };
+
+        match result {
+            Ok(()) => {}
+            Err(block_errors) => {
+                errors.extend(block_errors.into_iter().map(|err| {
+                    err.offset_lines(first_line)
+                        .in_file(ledger_file_path.to_owned())
+                }));
+
+                if mode == ParseMode::FailFast {
+                    return Err(errors.remove(0));
                 }
-                Err(error) => return Err(Error::from(error)),
             }
         }
     }
 
-    Ok(transactions)
+    match errors.len() {
+        0 => Ok((transactions, directives)),
+        1 => Err(errors.remove(0)),
+        _ => Err(Error::multiple(errors)),
+    }
+}
+
+/// Reads the commodity and currency declarations ahead of everything else,
+/// since amounts anywhere in the file are read with them. Malformed ones are
+/// skipped here and reported along with the rest of the file.
+pub(super) fn declared_commodities(blocks: &[(usize, String)]) -> Commodities {
+    let directives = blocks
+        .iter()
+        .filter(|(_, block)| {
+            let header = block.lines().next().unwrap_or_default();
+            matches!(
+                split_with_columns(header).get(1),
+                Some((_, keyword)) if [Commodity::KEYWORD, Currency::KEYWORD].contains(keyword)
+            )
+        })
+        .filter_map(|(_, block)| Directive::parse(block.as_str()).ok())
+        .collect::<Vec<Directive>>();
+
+    Commodities::from_directives(directives.iter())
+}
+
+pub(super) fn into_string(ledger_file_path: &Path, bytes: Vec<u8>) -> Result<String, Error> {
+    String::from_utf8(bytes).map_err(|err| {
+        let valid = &err.as_bytes()[..err.utf8_error().valid_up_to()];
+        let line = valid.iter().filter(|byte| **byte == b'\n').count();
+        let line_start = valid
+            .iter()
+            .rposition(|byte| *byte == b'\n')
+            .map_or(0, |idx| idx + 1);
+        let text = String::from_utf8_lossy(&err.as_bytes()[line_start..]);
+        let text = text.lines().next().unwrap_or_default();
+
+        Error::corrupted_ledger_file()
+            .at_columns(valid.len() - line_start..valid.len() - line_start + 1)
+            .at_line(line + 1, text)
+            .in_file(ledger_file_path.to_owned())
+    })
+}
+
+/// Splits a ledger file into its blank-line separated transactions, along
+/// with the number of lines that precede each one.
+pub(super) fn transaction_blocks(contents: &str) -> Vec<(usize, String)> {
+    let mut blocks = Vec::<(usize, String)>::new();
+    let mut current: Option<(usize, String)> = None;
+
+    for (idx, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            blocks.extend(current.take());
+            continue;
+        }
+
+        let (_, block) = current.get_or_insert_with(|| (idx, String::new()));
+        block.push_str(line);
+        block.push('\n');
+    }
+
+    blocks.extend(current);
+    blocks
 }
@@ -0,0 +1,5 @@
+use crate::{account::Account, balances::Balances, transaction::Transaction};
+
+pub fn reconcile_accounts(transactions: &[Transaction]) -> Vec<Account> {
+    transactions.iter().collect::<Balances>().into_accounts()
+}
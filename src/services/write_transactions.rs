@@ -1,19 +1,199 @@
-use std::{fs::OpenOptions, io::Write, path::Path};
+use std::{
+    ffi::OsString,
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
 
-use crate::{error::Error, transaction::Transaction};
+use ulid::Ulid;
 
-pub fn write_transactions<'p>(
-    ledger_file_path: &'p Path,
+use crate::{directive::Directive, error::Error, transaction::Transaction};
+
+use super::ledger_version::{ledger_header, upgrade_backups};
+
+/// Writes every transaction to the ledger file, replacing its contents.
+///
+/// The transactions are first written and synced to a temporary file next to
+/// the ledger, which is then renamed over it, so a crash part way through
+/// leaves either the old or the new ledger behind, never a mix of the two.
+/// When `backups` is non-zero, the previous ledger is kept as
+/// `<ledger>.bak.1`, older copies are shifted along, and anything past
+/// `<ledger>.bak.<backups>` is discarded. A ledger written in an older
+/// version of the text format is always backed up once, since older versions
+/// of sixp can't read what replaces it.
+pub fn write_transactions(
+    ledger_file_path: &Path,
+    transactions: &[Transaction],
+    backups: usize,
+) -> Result<(), Error> {
+    write_ledger(ledger_file_path, transactions, &[], backups)
+}
+
+/// Writes every transaction and directive to the ledger file, the same way
+/// as [`write_transactions`]. Both are expected in date order; a directive
+/// is written after the transactions dated the same day. The file always
+/// starts with the header line of the current version of the format.
+pub fn write_ledger(
+    ledger_file_path: &Path,
     transactions: &[Transaction],
+    directives: &[Directive],
+    backups: usize,
+) -> Result<(), Error> {
+    let contents = encode_ledger(transactions.iter(), directives);
+
+    replace_ledger_file(ledger_file_path, contents.as_bytes(), backups)
+}
+
+/// Writes out the contents of a text ledger file, as described in
+/// [`write_ledger`].
+pub(crate) fn encode_ledger<'a>(
+    transactions: impl ExactSizeIterator<Item = &'a Transaction>,
+    directives: &[Directive],
+) -> String {
+    let mut blocks = Vec::<String>::with_capacity(transactions.len() + directives.len() + 1);
+    blocks.push(ledger_header());
+
+    let mut directives = directives.iter().peekable();
+    for transaction in transactions {
+        while let Some(directive) =
+            directives.next_if(|directive| directive.date() < transaction.date())
+        {
+            blocks.push(directive.to_string());
+        }
+
+        blocks.push(transaction.to_string());
+    }
+    blocks.extend(directives.map(ToString::to_string));
+
+    blocks.join("\n\n")
+}
+
+/// Atomically replaces the contents of the ledger file, as described in
+/// [`write_transactions`].
+pub(crate) fn replace_ledger_file(
+    ledger_file_path: &Path,
+    contents: &[u8],
+    backups: usize,
 ) -> Result<(), Error> {
-    let mut file = OpenOptions::new().write(true).open(ledger_file_path)?;
-    let transactions = transactions
-        .iter()
-        .map(ToString::to_string)
-        .collect::<Vec<String>>()
-        .join("\n\n");
+    let backups = upgrade_backups(ledger_file_path, backups)?;
+    let temp_file_path = temp_file_path(ledger_file_path);
+    let result = write_temp_file(temp_file_path.as_path(), ledger_file_path, contents)
+        .and_then(|_| rotate_backups(ledger_file_path, backups))
+        .and_then(|_| fs::rename(temp_file_path.as_path(), ledger_file_path).map_err(Error::from))
+        .and_then(|_| sync_parent_dir(ledger_file_path));
+
+    if result.is_err() {
+        let _ = fs::remove_file(temp_file_path);
+    }
+
+    result
+}
 
-    write!(file, "{}", transactions)?;
+/// The path of the `n`th most recent backup of a ledger file, starting at 1.
+pub fn backup_file_path(ledger_file_path: &Path, n: usize) -> PathBuf {
+    let mut file_name = ledger_file_path
+        .file_name()
+        .map(OsString::from)
+        .unwrap_or_default();
+    file_name.push(format!(".bak.{}", n));
+
+    ledger_file_path.with_file_name(file_name)
+}
+
+/// A fresh path for the temporary file a new ledger is written to. Each
+/// write gets its own, so one left behind by a crash can't get in the way.
+fn temp_file_path(ledger_file_path: &Path) -> PathBuf {
+    let mut file_name = OsString::from(".");
+    file_name.push(ledger_file_path.file_name().unwrap_or_default());
+    file_name.push(format!(".tmp.{}", Ulid::new()));
+
+    ledger_file_path.with_file_name(file_name)
+}
+
+fn write_temp_file(
+    temp_file_path: &Path,
+    ledger_file_path: &Path,
+    contents: &[u8],
+) -> Result<(), Error> {
+    let mut file = OpenOptions::new()
+        .create_new(true)
+        .write(true)
+        .open(temp_file_path)?;
+
+    if let Ok(metadata) = fs::metadata(ledger_file_path) {
+        file.set_permissions(metadata.permissions())?;
+    }
+
+    file.write_all(contents)?;
+    file.sync_all()?;
+
+    Ok(())
+}
+
+/// Shifts existing backups along and links or copies the current ledger into
+/// `<ledger>.bak.1`, just before it's replaced.
+fn rotate_backups(ledger_file_path: &Path, backups: usize) -> Result<(), Error> {
+    if backups == 0 || !ledger_file_path.exists() {
+        return Ok(());
+    }
+
+    let oldest = backup_file_path(ledger_file_path, backups);
+    if oldest.exists() {
+        fs::remove_file(oldest)?;
+    }
+
+    for n in (1..backups).rev() {
+        let backup = backup_file_path(ledger_file_path, n);
+        if backup.exists() {
+            fs::rename(backup, backup_file_path(ledger_file_path, n + 1))?;
+        }
+    }
+
+    // The ledger itself has to stay in place until the new one is renamed
+    // over it, so the newest backup is a link to (or copy of) the same data.
+    let newest = backup_file_path(ledger_file_path, 1);
+    if fs::hard_link(ledger_file_path, newest.as_path()).is_err() {
+        fs::copy(ledger_file_path, newest.as_path())?;
+        File::open(newest)?.sync_all()?;
+    }
 
     Ok(())
 }
+
+#[cfg(unix)]
+fn sync_parent_dir(ledger_file_path: &Path) -> Result<(), Error> {
+    let parent = match ledger_file_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    File::open(parent)?.sync_all()?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn sync_parent_dir(_ledger_file_path: &Path) -> Result<(), Error> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn writes_past_a_temp_file_left_behind() {
+        let path = env::temp_dir().join(format!("sixp-test-{}.txt", Ulid::new()));
+        let stale = temp_file_path(path.as_path());
+        fs::write(stale.as_path(), "left behind").unwrap();
+
+        let result = replace_ledger_file(path.as_path(), b"sixpence ledger v2\n", 0);
+        let contents = fs::read_to_string(path.as_path());
+        let _ = fs::remove_file(stale);
+        let _ = fs::remove_file(path);
+
+        result.unwrap();
+        assert_eq!(contents.unwrap(), "sixpence ledger v2\n");
+    }
+}
@@ -0,0 +1,74 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+/// Where a transaction is in its lifecycle, from being entered to being
+/// checked against a bank statement.
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize,
+)]
+pub enum Status {
+    /// Entered, but not yet seen by the bank.
+    Uncleared,
+
+    /// Seen by the bank, but not yet settled.
+    Pending,
+
+    /// Settled by the bank, but not yet matched against a statement.
+    #[default]
+    Cleared,
+
+    /// Cleared through the bank and matched against a statement.
+    Reconciled,
+}
+
+impl Status {
+    /// The marker written after the id in a transaction's header line.
+    /// Cleared transactions have none, which keeps ledgers written back when
+    /// `*` only meant "not cleared" reading the same way.
+    pub fn marker(&self) -> Option<char> {
+        match self {
+            Status::Uncleared => Some('*'),
+            Status::Pending => Some('!'),
+            Status::Cleared => None,
+            Status::Reconciled => Some('R'),
+        }
+    }
+
+    /// The status a header line marker stands for.
+    pub fn from_marker(marker: char) -> Option<Self> {
+        match marker {
+            '*' => Some(Status::Uncleared),
+            '!' => Some(Status::Pending),
+            'R' => Some(Status::Reconciled),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Uncleared => write!(f, "uncleared"),
+            Status::Pending => write!(f, "pending"),
+            Status::Cleared => write!(f, "cleared"),
+            Status::Reconciled => write!(f, "reconciled"),
+        }
+    }
+}
+
+impl FromStr for Status {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "uncleared" | "not-cleared" => Ok(Status::Uncleared),
+            "pending" => Ok(Status::Pending),
+            "cleared" => Ok(Status::Cleared),
+            "reconciled" => Ok(Status::Reconciled),
+            _ => Err(Error::invalid_transaction_status(s)),
+        }
+    }
+}
@@ -1,21 +1,29 @@
-use std::{fmt::Display, ops::Neg};
+use std::{cmp::Ordering, fmt::Display, ops::Range};
 
 use chrono::{Date, NaiveDate, TimeZone, Utc};
-use getset::{CopyGetters, Getters};
+use getset::{CopyGetters, Getters, Setters};
+use serde::{Deserialize, Serialize};
 use ulid::Ulid;
 
-use crate::{cli::TransactionOpts, error::Error};
+use crate::{
+    amount::{divide_rounded, split_trailing_amount, Amount, Amounts},
+    commodity::Commodities,
+    error::Error,
+    services::LEDGER_VERSION,
+    status::Status,
+};
 
-#[derive(CopyGetters, Debug, Eq, Getters, PartialEq, Ord)]
+#[derive(CopyGetters, Debug, Deserialize, Eq, Getters, PartialEq, Serialize, Setters)]
 pub struct Transaction {
     #[getset(get_copy = "pub")]
+    #[serde(with = "crate::serde_date")]
     date: Date<Utc>,
 
     #[getset(get_copy = "pub")]
     id: Ulid,
 
-    #[getset(get_copy = "pub")]
-    has_cleared: bool,
+    #[getset(get_copy = "pub", set = "pub")]
+    status: Status,
 
     #[getset(get = "pub")]
     description: String,
@@ -28,96 +36,153 @@ impl Transaction {
     pub fn new(
         date: Date<Utc>,
         id: Ulid,
-        has_cleared: bool,
+        status: Status,
         description: String,
         entries: Vec<Entry>,
     ) -> Self {
         Self {
             date,
             id,
-            has_cleared,
+            status,
             description,
             entries,
         }
     }
-}
 
-impl PartialOrd for Transaction {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.date().partial_cmp(&other.date())
+    /// The sum of every entry's weight per commodity, which is zero for a
+    /// balanced transaction.
+    pub fn balance(&self, commodities: &Commodities) -> Amounts {
+        self.entries
+            .iter()
+            .map(|entry| entry.weight(commodities))
+            .collect()
     }
-}
 
-impl TryFrom<&TransactionOpts> for Transaction {
-    type Error = Error;
+    /// Checks that the transaction has entries, and that they balance.
+    pub fn validate(&self, commodities: &Commodities) -> Result<(), Error> {
+        if self.entries.is_empty() {
+            Err(Error::missing_transaction_entries())
+        } else if !self.balance(commodities).is_zero() {
+            Err(Error::unbalanced_transaction_entries())
+        } else {
+            Ok(())
+        }
+    }
 
-    fn try_from(opts: &TransactionOpts) -> Result<Self, Self::Error> {
-        match opts.entries().is_empty() {
-            true => Err(Error::missing_transaction_entries()),
-            false => {
-                let entries = validate_and_normalize_entries(opts.entries())?;
-
-                Ok(Self::new(
-                    opts.date(),
-                    Ulid::new(),
-                    !opts.has_not_cleared(),
-                    opts.description()
-                        .as_ref()
-                        .map(|desc| desc.to_owned())
-                        .unwrap_or_default(),
-                    entries,
-                ))
-            }
+    /// Builds a new transaction dated `date` that cancels this one out, with
+    /// every entry negated and a description pointing back at this one. The
+    /// bank hasn't seen the reversal yet, so it starts out uncleared.
+    pub fn reversal(&self, date: Date<Utc>) -> Self {
+        let mut entries = self
+            .entries
+            .iter()
+            .map(|entry| Entry {
+                amount: -entry.amount.clone(),
+                ..entry.clone()
+            })
+            .collect::<Vec<Entry>>();
+        entries.sort();
+
+        let description = match self.description.is_empty() {
+            true => format!("Void of {}", self.id),
+            false => format!("Void of {}: {}", self.id, self.description),
+        };
+
+        Self {
+            date,
+            id: Ulid::new(),
+            status: Status::Uncleared,
+            description,
+            entries,
         }
     }
 }
 
-impl TryFrom<String> for Transaction {
-    type Error = Error;
+impl Ord for Transaction {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.date
+            .cmp(&other.date)
+            .then_with(|| self.id.cmp(&other.id))
+    }
+}
 
-    fn try_from(serialized_entry: String) -> Result<Self, Self::Error> {
-        let mut date: Date<Utc> = Utc.timestamp_nanos(0).date();
-        let mut id = Ulid::new();
-        let mut has_cleared = true;
-        let mut description = String::new();
+impl PartialOrd for Transaction {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Transaction {
+    /// Parses a serialized transaction, reporting every malformed line
+    /// instead of stopping at the first one. Errors carry the 1-indexed line
+    /// number within `serialized_entry`.
+    pub fn parse(serialized_entry: &str) -> Result<Self, Vec<Error>> {
+        Self::parse_with(serialized_entry, &Commodities::new())
+    }
+
+    /// Parses a serialized transaction like [`Transaction::parse`], reading
+    /// amounts in the ledger's currency format and the precision of their
+    /// commodities.
+    pub fn parse_with(
+        serialized_entry: &str,
+        commodities: &Commodities,
+    ) -> Result<Self, Vec<Error>> {
+        Self::parse_in_version(serialized_entry, commodities, LEDGER_VERSION)
+    }
+
+    /// Parses a serialized transaction like [`Transaction::parse_with`], as
+    /// written in `version` of the text ledger format, bringing it up to the
+    /// current one.
+    pub(crate) fn parse_in_version(
+        serialized_entry: &str,
+        commodities: &Commodities,
+        version: u32,
+    ) -> Result<Self, Vec<Error>> {
+        let mut header = None;
         let mut entries = Vec::<Entry>::with_capacity(2);
+        let mut errors = Vec::<Error>::new();
 
-        for line in serialized_entry.split("\n") {
-            if line.starts_with('\t') {
-                entries.push(Entry::try_from(line)?);
+        for (idx, line) in serialized_entry.lines().enumerate() {
+            let result = if line.trim().is_empty() {
+                continue;
+            } else if line.starts_with('\t') {
+                Entry::parse(line, commodities).map(|entry| entries.push(entry))
             } else {
-                let values = line.split(" ").collect::<Vec<&str>>();
-                if values.len() < 2 {
-                    if values.is_empty() {
-                        return Err(Error::missing_transaction_field("date"));
-                    } else {
-                        return Err(Error::missing_transaction_field("id"));
-                    }
-                }
+                parse_header(line, version).map(|parsed| header = Some(parsed))
+            };
 
-                date = Utc
-                    .from_local_date(&NaiveDate::parse_from_str(values[0], "%Y-%m-%d")?)
-                    .unwrap();
+            if let Err(err) = result {
+                errors.push(err.at_line(idx + 1, line));
+            }
+        }
 
-                id = Ulid::from_string(values[1])?;
+        if !errors.is_empty() {
+            return Err(errors);
+        }
 
-                for value in values.iter().skip(2) {
-                    if *value == "*" {
-                        has_cleared = false;
-                    } else {
-                        description = (*value).to_owned();
-                    }
-                }
+        match header {
+            Some((date, id, status, description)) => Ok(Self {
+                id,
+                date,
+                status,
+                description,
+                entries,
+            }),
+            None => {
+                let first_line = serialized_entry.lines().next().unwrap_or_default();
+                Err(vec![
+                    Error::missing_transaction_field("date").at_line(1, first_line)
+                ])
             }
         }
+    }
+}
 
-        Ok(Self {
-            id,
-            date,
-            has_cleared,
-            description,
-            entries,
-        })
+impl TryFrom<String> for Transaction {
+    type Error = Error;
+
+    fn try_from(serialized_entry: String) -> Result<Self, Self::Error> {
+        Self::parse(serialized_entry.as_str()).map_err(|mut errors| errors.remove(0))
     }
 }
 
@@ -125,13 +190,16 @@ impl Display for Transaction {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let date = self.date.format("%Y-%m-%d");
 
-        let has_cleared = if !self.has_cleared {
-            format!(" *")
-        } else {
-            String::new()
+        let status = match self.status.marker() {
+            Some(marker) => format!(" {}", marker),
+            None => String::new(),
         };
 
-        let description = format!(" {}", self.description);
+        let description = if self.description.is_empty() {
+            String::new()
+        } else {
+            format!(" {}", quote_description(self.description.as_str()))
+        };
 
         let entries = self
             .entries
@@ -143,135 +211,712 @@ impl Display for Transaction {
         write!(
             f,
             "{} {}{}{}\n{}",
-            date, self.id, has_cleared, description, entries
+            date, self.id, status, description, entries
         )
     }
 }
 
-#[derive(Clone, CopyGetters, Debug, Default, Eq, Getters, PartialEq, Ord)]
+#[derive(Clone, CopyGetters, Debug, Default, Deserialize, Eq, Getters, PartialEq, Serialize)]
 pub struct Entry {
     #[getset(get = "pub")]
     account: String,
 
+    #[getset(get = "pub")]
+    amount: Amount,
+
+    /// What each whole unit of the amount cost when it was bought, for
+    /// entries that open or draw down a lot.
+    #[getset(get = "pub")]
+    cost: Option<Amount>,
+
+    /// When the lot an entry draws down was bought, which together with its
+    /// cost picks out the lot.
     #[getset(get_copy = "pub")]
-    value: isize,
+    #[serde(with = "crate::serde_date::option")]
+    lot_date: Option<Date<Utc>>,
+
+    /// What each whole unit of the amount sold or traded for.
+    #[getset(get = "pub")]
+    price: Option<Amount>,
 }
 
 impl Entry {
+    /// An entry in the ledger's home currency.
     pub fn new(account: &str, value: isize) -> Self {
+        Self::with_amount(account, Amount::of_default(value))
+    }
+
+    pub fn with_amount(account: &str, amount: Amount) -> Self {
         Self {
             account: account.to_owned(),
-            value,
+            amount,
+            cost: None,
+            lot_date: None,
+            price: None,
+        }
+    }
+
+    /// An entry with a per-unit cost or price. A price on an amount coming
+    /// into the account is what it was bought for, so it becomes the cost of
+    /// a new lot. Only an entry drawing down a lot can give the lot's date.
+    pub fn annotated(
+        account: &str,
+        amount: Amount,
+        cost: Option<Amount>,
+        lot_date: Option<Date<Utc>>,
+        price: Option<Amount>,
+    ) -> Result<Self, Error> {
+        let priced_in_itself = cost
+            .iter()
+            .chain(price.iter())
+            .any(|per_unit| per_unit.commodity() == amount.commodity());
+        if priced_in_itself {
+            return Err(Error::invalid_cost("a commodity can't be priced in itself"));
+        }
+        if lot_date.is_some() && (cost.is_none() || amount.value() >= 0) {
+            return Err(Error::invalid_cost(
+                "only units taken out of a lot can give its date",
+            ));
+        }
+
+        let (cost, price) = match (cost, price) {
+            (None, Some(price)) if amount.value() > 0 => (Some(price), None),
+            annotations => annotations,
+        };
+
+        Ok(Self {
+            account: account.to_owned(),
+            amount,
+            cost,
+            lot_date,
+            price,
+        })
+    }
+
+    /// The entry's value, in the smallest unit of its commodity.
+    pub fn value(&self) -> isize {
+        self.amount.value()
+    }
+
+    pub fn commodity(&self) -> &str {
+        self.amount.commodity().as_str()
+    }
+
+    /// Whether the entry sells units out of a lot, and so has a gain or loss.
+    pub fn is_sale(&self) -> bool {
+        self.cost.is_some() && self.price.is_some()
+    }
+
+    /// What the entry counts for when balancing a transaction: its cost if it
+    /// has one, or else its price, or else the amount itself.
+    pub fn weight(&self, commodities: &Commodities) -> Amount {
+        self.worth(self.cost.as_ref().or(self.price.as_ref()), commodities)
+    }
+
+    /// What the entry fetched at its price, which for a sale differs from its
+    /// weight by the gain or loss.
+    pub fn proceeds(&self, commodities: &Commodities) -> Amount {
+        self.worth(self.price.as_ref().or(self.cost.as_ref()), commodities)
+    }
+
+    fn worth(&self, per_unit: Option<&Amount>, commodities: &Commodities) -> Amount {
+        match per_unit {
+            Some(per_unit) => {
+                let units = 10i128.pow(commodities.precision(self.commodity()));
+                let value = divide_rounded(self.value() as i128 * per_unit.value() as i128, units);
+
+                Amount::new(value as isize, per_unit.commodity())
+            }
+            None => self.amount.clone(),
         }
     }
 }
 
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.account
+            .cmp(&other.account)
+            .then_with(|| self.amount.commodity().cmp(other.amount.commodity()))
+            .then_with(|| self.amount.value().cmp(&other.amount.value()))
+            .then_with(|| self.cost.cmp(&other.cost))
+            .then_with(|| self.lot_date.cmp(&other.lot_date))
+            .then_with(|| self.price.cmp(&other.price))
+    }
+}
+
 impl PartialOrd for Entry {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.account.partial_cmp(other.account())
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
-impl From<&(String, Option<isize>)> for Entry {
-    fn from((account, value): &(String, Option<isize>)) -> Self {
-        let value = match value {
-            Some(value) => *value,
-            None => 0,
-        };
+impl TryFrom<&DraftEntry> for Entry {
+    type Error = Error;
 
-        Self {
-            account: account.to_owned(),
-            value,
+    fn try_from(draft: &DraftEntry) -> Result<Self, Self::Error> {
+        Self::annotated(
+            draft.account(),
+            draft.amount().clone().unwrap_or_default(),
+            draft.cost().clone(),
+            draft.lot_date(),
+            draft.price().clone(),
+        )
+    }
+}
+
+impl Entry {
+    /// Parses an entry line, reading amounts in the ledger's currency format
+    /// and the precision of their commodities.
+    pub fn parse(line: &str, commodities: &Commodities) -> Result<Self, Error> {
+        let parts = split_with_columns(line);
+        let (parts, price) = match parts.iter().rposition(|(_, part)| *part == "@") {
+            Some(idx) => (
+                &parts[..idx],
+                Some(parse_per_unit(&parts[idx..], commodities)?),
+            ),
+            None => (parts.as_slice(), None),
+        };
+        let (parts, cost, lot_date) =
+            match parts.iter().rposition(|(_, part)| part.starts_with('{')) {
+                Some(idx) if parts.last().is_some_and(|(_, part)| part.ends_with('}')) => {
+                    let (cost, lot_date) = parse_cost(&parts[idx..], commodities)?;
+                    (&parts[..idx], Some(cost), lot_date)
+                }
+                _ => (parts, None, None),
+            };
+
+        if parts.len() < 2 {
+            let end = parts
+                .last()
+                .map(|(columns, _)| columns.end)
+                .unwrap_or_else(|| line.trim_end().len());
+            return Err(Error::blank_entry_value().at_columns(end..end + 1));
         }
+
+        let (start, amount) = split_trailing_amount(&parts[1..], commodities)?;
+        let account = line[..start].trim();
+        let columns = parts[0].0.start..line.trim_end().len();
+
+        Self::annotated(account, amount, cost, lot_date, price)
+            .map_err(|err| err.at_columns(columns))
     }
 }
 
 impl TryFrom<&str> for Entry {
     type Error = Error;
 
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let parts = value.trim().split(" ").collect::<Vec<&str>>();
-        let value = parts
-            .last()
-            .unwrap()
-            .parse::<isize>()
-            .map_err(|_| Error::blank_entry_value())?;
+    fn try_from(line: &str) -> Result<Self, Self::Error> {
+        Self::parse(line, &Commodities::new())
+    }
+}
 
-        let account = parts
-            .iter()
-            .enumerate()
-            .filter_map(|(idx, part)| {
-                if idx == parts.len() - 1 {
-                    None
-                } else {
-                    Some(*part)
+impl Display for Entry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "\t{} {}", self.account, self.amount)?;
+
+        match (&self.cost, &self.price) {
+            (Some(cost), None) if self.value() > 0 => write!(f, " @ {}", cost),
+            (Some(cost), _) => {
+                match self.lot_date {
+                    Some(lot_date) => write!(f, " {{{}, {}}}", cost, lot_date.format("%Y-%m-%d")),
+                    None => write!(f, " {{{}}}", cost),
+                }?;
+
+                match &self.price {
+                    Some(price) => write!(f, " @ {}", price),
+                    None => Ok(()),
                 }
-            })
-            .collect::<Vec<&str>>()
-            .join(" ");
+            }
+            (None, Some(price)) => write!(f, " @ {}", price),
+            (None, None) => Ok(()),
+        }
+    }
+}
 
-        Ok(Self { account, value })
+/// Parses an entry's `{cost}`, or `{cost, date}` when it names the lot it
+/// draws down, given the parts of the line it takes up, braces included.
+fn parse_cost(
+    parts: &[(Range<usize>, &str)],
+    commodities: &Commodities,
+) -> Result<(Amount, Option<Date<Utc>>), Error> {
+    match parts {
+        [cost @ .., (columns, date)]
+            if cost.last().is_some_and(|(_, part)| part.ends_with(',')) =>
+        {
+            let date = date.trim_end_matches('}');
+            let lot_date = parse_date(date, columns.start..columns.start + date.len())?;
+
+            let mut cost = cost.to_vec();
+            if let Some((columns, part)) = cost.last_mut() {
+                *part = part.trim_end_matches(',');
+                columns.end = columns.start + part.len();
+            }
+
+            Ok((
+                parse_per_unit(cost.as_slice(), commodities)?,
+                Some(lot_date),
+            ))
+        }
+        _ => Ok((parse_per_unit(parts, commodities)?, None)),
     }
 }
 
-impl Display for Entry {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "\t{} {}", self.account, self.value)
+/// Parses the per-unit amount of an entry's `{cost}` or `@ price`, given the
+/// parts of the line it takes up, braces or `@` included.
+fn parse_per_unit(
+    parts: &[(Range<usize>, &str)],
+    commodities: &Commodities,
+) -> Result<Amount, Error> {
+    let (first_columns, first) = &parts[0];
+    let mut values = parts
+        .iter()
+        .map(|(columns, part)| {
+            let trimmed = part.trim_start_matches(['{', '@']);
+            let start = columns.start + part.len() - trimmed.len();
+            let part = trimmed.trim_end_matches('}');
+
+            (start..start + part.len(), part)
+        })
+        .filter(|(_, part)| !part.is_empty())
+        .collect::<Vec<(Range<usize>, &str)>>();
+
+    let end = parts
+        .last()
+        .map(|(columns, _)| columns.end)
+        .unwrap_or_default();
+    if values.is_empty() {
+        let what = match first.starts_with('@') {
+            true => "a price needs a value",
+            false => "a cost needs a value",
+        };
+        return Err(Error::invalid_cost(what).at_columns(first_columns.start..end));
+    }
+
+    let (start, amount) = split_trailing_amount(values.as_slice(), commodities)?;
+    values.retain(|(columns, _)| columns.start < start);
+    match values.first() {
+        Some((columns, _)) => {
+            Err(Error::invalid_cost("expected a single amount").at_columns(columns.start..start))
+        }
+        None => Ok(amount),
     }
 }
 
-fn validate_and_normalize_entries(
-    entries: &[(String, Option<isize>)],
-) -> Result<Vec<Entry>, Error> {
-    let entries = {
-        let number_of_entries_with_value =
-            entries.iter().filter(|(_, value)| value.is_some()).count();
-        if number_of_entries_with_value == entries.len() && entries.len() > 1 {
-            entries.to_owned()
-        } else {
-            entries
-                .to_owned()
-                .into_iter()
-                .chain(vec![("Equities:Starting Balances".to_owned(), None)])
-                .collect()
+/// Parses the description at the end of a transaction's header line.
+///
+/// Descriptions are written in double quotes, with `\"`, `\\`, `\n` and `\t`
+/// as escapes. Ledgers written before descriptions were quoted left them
+/// bare, so an unquoted description is taken verbatim up to the end of the
+/// line.
+pub(crate) fn parse_description(text: &str) -> Result<String, Error> {
+    let quoted = match text.strip_prefix('"') {
+        Some(quoted) => quoted,
+        None => return Ok(text.trim_end().to_owned()),
+    };
+
+    let mut description = String::with_capacity(quoted.len());
+    let mut chars = quoted.char_indices().map(|(idx, c)| (idx + 1, c));
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '"' => {
+                let trailing = &text[idx + 1..];
+                return match trailing.trim().is_empty() {
+                    true => Ok(description),
+                    false => Err(Error::invalid_transaction_description(
+                        "unexpected text after the closing quote",
+                    )
+                    .at_columns(idx + 1..text.trim_end().len())),
+                };
+            }
+            '\\' => match chars.next() {
+                Some((_, '"')) => description.push('"'),
+                Some((_, '\\')) => description.push('\\'),
+                Some((_, 'n')) => description.push('\n'),
+                Some((_, 't')) => description.push('\t'),
+                Some((end, _)) => {
+                    return Err(
+                        Error::invalid_transaction_description("unknown escape sequence")
+                            .at_columns(idx..end + 1),
+                    )
+                }
+                None => break,
+            },
+            c => description.push(c),
+        }
+    }
+
+    Err(
+        Error::invalid_transaction_description("missing the closing quote")
+            .at_columns(0..text.trim_end().len()),
+    )
+}
+
+/// Wraps a description in double quotes, escaping it so that
+/// [`parse_description`] reads it back unchanged.
+pub(crate) fn quote_description(description: &str) -> String {
+    let mut quoted = String::with_capacity(description.len() + 2);
+    quoted.push('"');
+    for c in description.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+/// Parses a transaction's header line, as written in `version` of the text
+/// ledger format, into its date, id, status and description.
+fn parse_header(line: &str, version: u32) -> Result<(Date<Utc>, Ulid, Status, String), Error> {
+    let values = split_with_columns(line);
+    if values.len() < 2 {
+        return Err(Error::missing_transaction_field("id").at_columns(line.len()..line.len() + 1));
+    }
+
+    let (date_columns, date) = &values[0];
+    let date = parse_date(date, date_columns.clone())?;
+
+    let (id_columns, id) = &values[1];
+    let id =
+        Ulid::from_string(id).map_err(|err| Error::from(err).at_columns(id_columns.clone()))?;
+
+    let mut rest = line[id_columns.end..].trim_start();
+    let (first, after) = rest.split_once(' ').unwrap_or((rest, ""));
+    let mut chars = first.chars();
+    let status = match (chars.next(), chars.next()) {
+        // Version 1 ledgers only ever marked uncleared transactions.
+        (Some('*'), None) if version == 1 => Some(Status::Uncleared),
+        (Some(_), None) if version == 1 => None,
+        (Some(marker), None) => Status::from_marker(marker),
+        _ => None,
+    };
+    let status = match status {
+        Some(status) => {
+            rest = after.trim_start();
+            status
         }
+        None => Status::Cleared,
     };
 
-    let values = entries
-        .iter()
-        .map(|(_, value)| *value)
-        .collect::<Vec<Option<isize>>>();
+    // Their descriptions were written bare, so quotes in them are part of the
+    // description.
+    if version == 1 {
+        return Ok((date, id, status, rest.trim_end().to_owned()));
+    }
+
+    let offset = line.len() - rest.len();
+    let description = parse_description(rest).map_err(|err| err.offset_columns(offset))?;
+
+    Ok((date, id, status, description))
+}
+
+/// Parses a `YYYY-MM-DD` date found at `columns` of a line.
+pub(crate) fn parse_date(text: &str, columns: Range<usize>) -> Result<Date<Utc>, Error> {
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .map(|date| Utc.from_utc_date(&date))
+        .map_err(|err| Error::from(err).at_columns(columns))
+}
+
+/// Splits a line on spaces, keeping track of the byte range of each part.
+/// Leading and trailing whitespace is ignored.
+pub(crate) fn split_with_columns(line: &str) -> Vec<(Range<usize>, &str)> {
+    let mut offset = line.len() - line.trim_start().len();
+    line.trim()
+        .split(' ')
+        .map(|part| {
+            let columns = offset..offset + part.len();
+            offset = columns.end + 1;
+            (columns, part)
+        })
+        .filter(|(_, part)| !part.is_empty())
+        .collect()
+}
+
+/// An entry as given on the command line, before its transaction is
+/// balanced. Its amount may be left out, and on a sale its cost and lot date
+/// pick the lots the units come out of.
+#[derive(Clone, CopyGetters, Debug, Default, Eq, Getters, PartialEq)]
+pub struct DraftEntry {
+    #[getset(get = "pub")]
+    account: String,
+
+    #[getset(get = "pub")]
+    amount: Option<Amount>,
+
+    #[getset(get = "pub")]
+    cost: Option<Amount>,
+
+    #[getset(get_copy = "pub")]
+    lot_date: Option<Date<Utc>>,
+
+    #[getset(get = "pub")]
+    price: Option<Amount>,
+}
 
-    let has_multiple_blank_values = values.iter().filter(|value| value.is_none()).count() > 1;
-    if has_multiple_blank_values {
-        return Err(Error::unbalanced_transaction_entries());
+impl DraftEntry {
+    pub fn new(
+        account: &str,
+        amount: Option<Amount>,
+        cost: Option<Amount>,
+        price: Option<Amount>,
+    ) -> Self {
+        Self {
+            account: account.to_owned(),
+            amount,
+            cost,
+            lot_date: None,
+            price,
+        }
+    }
+
+    /// The same entry, drawing down only lots bought on `lot_date`.
+    pub fn with_lot_date(mut self, lot_date: Option<Date<Utc>>) -> Self {
+        self.lot_date = lot_date;
+        self
+    }
+
+    /// Whether the entry has a cost or price, and so needs the ledger's lots
+    /// and commodities to be balanced.
+    pub fn is_annotated(&self) -> bool {
+        self.cost.is_some() || self.lot_date.is_some() || self.price.is_some()
+    }
+}
+
+/// Turns a list of draft entries into balanced entries. Every entry has to
+/// name an account.
+///
+/// Every commodity has to balance on its own, with entries that have a cost
+/// or price counting for what they're worth in it. At most one entry may
+/// leave out its amount, in which case it receives whatever is needed to
+/// bring each commodity to zero, split into one entry per commodity. A lone
+/// entry, as when recording an account's starting balance, is offset against
+/// `Equities:Starting Balances`; otherwise entries that don't balance are an
+/// error.
+///
+/// When units are sold out of a lot, the blank entry receives the sale's
+/// proceeds, and the difference between those and the lot's cost is booked to
+/// `Income:Capital Gains`. Without a blank entry, the other entries have to
+/// balance against the proceeds themselves.
+pub fn validate_and_normalize_entries(
+    entries: &[DraftEntry],
+    commodities: &Commodities,
+) -> Result<Vec<Entry>, Error> {
+    let mut normalized = Vec::<Entry>::with_capacity(entries.len() + 2);
+    let mut blank_entries = Vec::<&str>::new();
+    for entry in entries.iter() {
+        if entry.account().trim().is_empty() {
+            return Err(Error::empty_account_name());
+        }
+
+        match entry.amount() {
+            Some(_) => normalized.push(Entry::try_from(entry)?),
+            None => blank_entries.push(entry.account()),
+        }
     }
 
-    let non_zero_values_sum = values
+    let has_sales = normalized.iter().any(Entry::is_sale);
+    let sums = normalized
         .iter()
-        .filter_map(|value| Some(value.unwrap_or(0)))
-        .sum();
-    let mut entries = match non_zero_values_sum {
-        0 => entries
-            .iter()
-            .map(|(account, value)| Entry::new(account.as_str(), value.unwrap()))
-            .collect(),
-        _ => entries
-            .iter()
-            .map(|(account, value)| {
-                let value = value.unwrap_or(0);
-                Entry::new(
-                    account.as_str(),
-                    if value == 0 {
-                        non_zero_values_sum.neg()
-                    } else {
-                        value
-                    },
-                )
-            })
-            .collect::<Vec<Entry>>(),
+        .map(|entry| entry.proceeds(commodities))
+        .collect::<Amounts>();
+
+    let blank_entry = match blank_entries.as_slice() {
+        [] if normalized.len() == 1 && !has_sales => Some("Equities:Starting Balances"),
+        [] if sums.is_zero() => None,
+        [] => return Err(Error::unbalanced_transaction_entries()),
+        [account] => Some(*account),
+        _ => return Err(Error::unbalanced_transaction_entries()),
     };
 
-    entries.sort();
-    Ok(entries)
+    match blank_entry {
+        Some(account) if sums.is_zero() => normalized.push(Entry::new(account, 0)),
+        Some(account) => {
+            normalized.extend(sums.iter().map(|sum| Entry::with_amount(account, -sum)))
+        }
+        None => {}
+    }
+
+    if has_sales {
+        let gains = normalized
+            .iter()
+            .map(|entry| entry.weight(commodities))
+            .collect::<Amounts>();
+        normalized.extend(
+            gains
+                .iter()
+                .map(|gain| Entry::with_amount("Income:Capital Gains", -gain)),
+        );
+    }
+
+    normalized.sort();
+    Ok(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::ErrorKind;
+
+    use super::*;
+
+    fn sale(proceeds: Option<isize>) -> Vec<DraftEntry> {
+        vec![
+            DraftEntry::new(
+                "Assets:Brokerage",
+                Some(Amount::new(-500, "AAPL")),
+                Some(Amount::of_default(15000)),
+                Some(Amount::of_default(17500)),
+            ),
+            DraftEntry::new("Assets:Cash", proceeds.map(Amount::of_default), None, None),
+        ]
+    }
+
+    #[test]
+    fn books_the_gain_on_a_sale_into_a_blank_entry() {
+        let entries = validate_and_normalize_entries(&sale(None), &Commodities::new()).unwrap();
+        assert!(entries.contains(&Entry::new("Assets:Cash", 87500)));
+        assert!(entries.contains(&Entry::new("Income:Capital Gains", -12500)));
+        assert_eq!(entries.len(), 3);
+    }
+
+    #[test]
+    fn books_the_gain_on_a_sale_at_its_proceeds() {
+        let entries =
+            validate_and_normalize_entries(&sale(Some(87500)), &Commodities::new()).unwrap();
+        assert!(entries.contains(&Entry::new("Income:Capital Gains", -12500)));
+        assert_eq!(entries.len(), 3);
+    }
+
+    #[test]
+    fn rejects_a_sale_that_doesnt_balance_against_its_proceeds() {
+        let err =
+            validate_and_normalize_entries(&sale(Some(875000)), &Commodities::new()).unwrap_err();
+        assert!(matches!(
+            err.kind(),
+            ErrorKind::UnbalancedTransactionEntries
+        ));
+    }
+
+    #[test]
+    fn offsets_a_lone_entry_against_starting_balances() {
+        let entries = [DraftEntry::new(
+            "Assets:Cash",
+            Some(Amount::of_default(10000)),
+            None,
+            None,
+        )];
+        let entries = validate_and_normalize_entries(&entries, &Commodities::new()).unwrap();
+        assert!(entries.contains(&Entry::new("Equities:Starting Balances", -10000)));
+    }
+
+    #[test]
+    fn rejects_entries_that_dont_balance() {
+        for entries in [
+            vec![
+                DraftEntry::new("Expenses:Food", Some(Amount::of_default(1000)), None, None),
+                DraftEntry::new("Assets:Cash", Some(Amount::of_default(-500)), None, None),
+            ],
+            vec![
+                DraftEntry::new("Assets:Euro", Some(Amount::new(1000, "EUR")), None, None),
+                DraftEntry::new("Assets:Bank", Some(Amount::of_default(-1100)), None, None),
+            ],
+        ] {
+            let err = validate_and_normalize_entries(&entries, &Commodities::new()).unwrap_err();
+            assert!(matches!(
+                err.kind(),
+                ErrorKind::UnbalancedTransactionEntries
+            ));
+        }
+    }
+
+    #[test]
+    fn rejects_entries_without_an_account() {
+        for account in ["", "  "] {
+            let entries = [
+                DraftEntry::new(account, Some(Amount::of_default(500)), None, None),
+                DraftEntry::new("Assets:Bank", None, None, None),
+            ];
+            let err = validate_and_normalize_entries(&entries, &Commodities::new()).unwrap_err();
+            assert!(matches!(err.kind(), ErrorKind::EmptyAccountName));
+        }
+    }
+
+    #[test]
+    fn quoted_descriptions_round_trip() {
+        for description in [
+            "",
+            "Groceries",
+            "  leading and trailing  ",
+            "Dinner at \"Chez Nous\"",
+            "C:\\Users\\me",
+            "two\nlines\tand a tab",
+            "Café — déjà vu",
+        ] {
+            let quoted = quote_description(description);
+            assert_eq!(parse_description(quoted.as_str()).unwrap(), description);
+        }
+    }
+
+    #[test]
+    fn reads_unmarked_headers_as_cleared() {
+        let id = Ulid::new();
+        for (marker, status) in [
+            ("", Status::Cleared),
+            (" *", Status::Uncleared),
+            (" !", Status::Pending),
+            (" R", Status::Reconciled),
+        ] {
+            let header = format!("2026-10-01 {}{} \"Groceries\"", id, marker);
+            let (_, _, parsed, description) =
+                parse_header(header.as_str(), LEDGER_VERSION).unwrap();
+            assert_eq!(parsed, status);
+            assert_eq!(description, "Groceries");
+        }
+    }
+
+    #[test]
+    fn reads_version_1_headers_with_only_the_uncleared_marker() {
+        let id = Ulid::new();
+        for (rest, status, description) in [
+            ("R Smith & Co", Status::Cleared, "R Smith & Co"),
+            ("! urgent", Status::Cleared, "! urgent"),
+            ("R", Status::Cleared, "R"),
+            ("* R", Status::Uncleared, "R"),
+            ("\"Quoted\" dinner", Status::Cleared, "\"Quoted\" dinner"),
+            ("", Status::Cleared, ""),
+        ] {
+            let block = format!(
+                "2026-01-02 {} {}\n\tAssets:Cash -500\n\tExpenses:Food 500",
+                id, rest
+            );
+            let transaction =
+                Transaction::parse_in_version(block.as_str(), &Commodities::new(), 1).unwrap();
+            assert_eq!(transaction.status(), status, "{}", rest);
+            assert_eq!(transaction.description(), description);
+        }
+    }
+
+    #[test]
+    fn reads_bare_descriptions_as_they_are() {
+        assert_eq!(parse_description("Groceries  ").unwrap(), "Groceries");
+    }
+
+    #[test]
+    fn rejects_malformed_quoted_descriptions() {
+        for text in [
+            "\"unterminated",
+            "\"bad \\q escape\"",
+            "\"closed\" trailing",
+        ] {
+            let err = parse_description(text).unwrap_err();
+            assert!(matches!(
+                err.kind(),
+                ErrorKind::InvalidTransactionDescription(_)
+            ));
+        }
+    }
 }